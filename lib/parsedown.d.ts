@@ -2,6 +2,15 @@
 // deno-lint-ignore-file
 // deno-fmt-ignore-file
 
+export function html_to_markdown(html: string): string;
+
+/**
+ * # Panics
+ *
+ * Will panic if unable to parse options
+ */
+export function html_to_plaintext(html: string, options: any): string;
+
 /**
  * # Panics
  *
@@ -9,6 +18,20 @@
  */
 export function markdown_to_html(markdown: string, options: any): any;
 
+/**
+ * # Panics
+ *
+ * Will panic if unable to parse options
+ */
+export function markdown_to_markdown(markdown: string, options: any): string;
+
+/**
+ * # Panics
+ *
+ * Will panic if unable to parse options
+ */
+export function markdown_to_mdast(markdown: string, options: any): any;
+
 /**
  * # Panics
  *
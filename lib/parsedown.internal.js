@@ -5,185 +5,227 @@
 
 let wasm;
 export function __wbg_set_wasm(val) {
-  wasm = val;
+    wasm = val;
+}
+
+function addToExternrefTable0(obj) {
+    const idx = wasm.__externref_table_alloc();
+    wasm.__wbindgen_externrefs.set(idx, obj);
+    return idx;
 }
 
 function debugString(val) {
-  // primitive types
-  const type = typeof val;
-  if (type == "number" || type == "boolean" || val == null) {
-    return `${val}`;
-  }
-  if (type == "string") {
-    return `"${val}"`;
-  }
-  if (type == "symbol") {
-    const description = val.description;
-    if (description == null) {
-      return "Symbol";
-    } else {
-      return `Symbol(${description})`;
+    // primitive types
+    const type = typeof val;
+    if (type == 'number' || type == 'boolean' || val == null) {
+        return  `${val}`;
     }
-  }
-  if (type == "function") {
-    const name = val.name;
-    if (typeof name == "string" && name.length > 0) {
-      return `Function(${name})`;
-    } else {
-      return "Function";
+    if (type == 'string') {
+        return `"${val}"`;
     }
-  }
-  // objects
-  if (Array.isArray(val)) {
-    const length = val.length;
-    let debug = "[";
-    if (length > 0) {
-      debug += debugString(val[0]);
+    if (type == 'symbol') {
+        const description = val.description;
+        if (description == null) {
+            return 'Symbol';
+        } else {
+            return `Symbol(${description})`;
+        }
     }
-    for (let i = 1; i < length; i++) {
-      debug += ", " + debugString(val[i]);
+    if (type == 'function') {
+        const name = val.name;
+        if (typeof name == 'string' && name.length > 0) {
+            return `Function(${name})`;
+        } else {
+            return 'Function';
+        }
     }
-    debug += "]";
-    return debug;
-  }
-  // Test for built-in
-  const builtInMatches = /\[object ([^\]]+)\]/.exec(toString.call(val));
-  let className;
-  if (builtInMatches && builtInMatches.length > 1) {
-    className = builtInMatches[1];
-  } else {
-    // Failed to match the standard '[object ClassName]'
-    return toString.call(val);
-  }
-  if (className == "Object") {
-    // we're a user defined class or Object
-    // JSON.stringify avoids problems with cycles, and is generally much
-    // easier than looping through ownProperties of `val`.
-    try {
-      return "Object(" + JSON.stringify(val) + ")";
-    } catch (_) {
-      return "Object";
+    // objects
+    if (Array.isArray(val)) {
+        const length = val.length;
+        let debug = '[';
+        if (length > 0) {
+            debug += debugString(val[0]);
+        }
+        for(let i = 1; i < length; i++) {
+            debug += ', ' + debugString(val[i]);
+        }
+        debug += ']';
+        return debug;
+    }
+    // Test for built-in
+    const builtInMatches = /\[object ([^\]]+)\]/.exec(toString.call(val));
+    let className;
+    if (builtInMatches && builtInMatches.length > 1) {
+        className = builtInMatches[1];
+    } else {
+        // Failed to match the standard '[object ClassName]'
+        return toString.call(val);
     }
-  }
-  // errors
-  if (val instanceof Error) {
-    return `${val.name}: ${val.message}\n${val.stack}`;
-  }
-  // TODO we could test for more things here, like `Set`s and `Map`s.
-  return className;
+    if (className == 'Object') {
+        // we're a user defined class or Object
+        // JSON.stringify avoids problems with cycles, and is generally much
+        // easier than looping through ownProperties of `val`.
+        try {
+            return 'Object(' + JSON.stringify(val) + ')';
+        } catch (_) {
+            return 'Object';
+        }
+    }
+    // errors
+    if (val instanceof Error) {
+        return `${val.name}: ${val.message}\n${val.stack}`;
+    }
+    // TODO we could test for more things here, like `Set`s and `Map`s.
+    return className;
 }
 
 function getArrayU8FromWasm0(ptr, len) {
-  ptr = ptr >>> 0;
-  return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
+    ptr = ptr >>> 0;
+    return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
 }
 
 let cachedDataViewMemory0 = null;
 function getDataViewMemory0() {
-  if (
-    cachedDataViewMemory0 === null ||
-    cachedDataViewMemory0.buffer.detached === true ||
-    (cachedDataViewMemory0.buffer.detached === undefined &&
-      cachedDataViewMemory0.buffer !== wasm.memory.buffer)
-  ) {
-    cachedDataViewMemory0 = new DataView(wasm.memory.buffer);
-  }
-  return cachedDataViewMemory0;
+    if (cachedDataViewMemory0 === null || cachedDataViewMemory0.buffer.detached === true || (cachedDataViewMemory0.buffer.detached === undefined && cachedDataViewMemory0.buffer !== wasm.memory.buffer)) {
+        cachedDataViewMemory0 = new DataView(wasm.memory.buffer);
+    }
+    return cachedDataViewMemory0;
 }
 
 function getStringFromWasm0(ptr, len) {
-  ptr = ptr >>> 0;
-  return decodeText(ptr, len);
+    ptr = ptr >>> 0;
+    return decodeText(ptr, len);
 }
 
 let cachedUint8ArrayMemory0 = null;
 function getUint8ArrayMemory0() {
-  if (
-    cachedUint8ArrayMemory0 === null || cachedUint8ArrayMemory0.byteLength === 0
-  ) {
-    cachedUint8ArrayMemory0 = new Uint8Array(wasm.memory.buffer);
-  }
-  return cachedUint8ArrayMemory0;
+    if (cachedUint8ArrayMemory0 === null || cachedUint8ArrayMemory0.byteLength === 0) {
+        cachedUint8ArrayMemory0 = new Uint8Array(wasm.memory.buffer);
+    }
+    return cachedUint8ArrayMemory0;
+}
+
+function handleError(f, args) {
+    try {
+        return f.apply(this, args);
+    } catch (e) {
+        const idx = addToExternrefTable0(e);
+        wasm.__wbindgen_exn_store(idx);
+    }
 }
 
 function isLikeNone(x) {
-  return x === undefined || x === null;
+    return x === undefined || x === null;
 }
 
 function passStringToWasm0(arg, malloc, realloc) {
-  if (realloc === undefined) {
-    const buf = cachedTextEncoder.encode(arg);
-    const ptr = malloc(buf.length, 1) >>> 0;
-    getUint8ArrayMemory0().subarray(ptr, ptr + buf.length).set(buf);
-    WASM_VECTOR_LEN = buf.length;
-    return ptr;
-  }
+    if (realloc === undefined) {
+        const buf = cachedTextEncoder.encode(arg);
+        const ptr = malloc(buf.length, 1) >>> 0;
+        getUint8ArrayMemory0().subarray(ptr, ptr + buf.length).set(buf);
+        WASM_VECTOR_LEN = buf.length;
+        return ptr;
+    }
 
-  let len = arg.length;
-  let ptr = malloc(len, 1) >>> 0;
+    let len = arg.length;
+    let ptr = malloc(len, 1) >>> 0;
 
-  const mem = getUint8ArrayMemory0();
+    const mem = getUint8ArrayMemory0();
 
-  let offset = 0;
+    let offset = 0;
 
-  for (; offset < len; offset++) {
-    const code = arg.charCodeAt(offset);
-    if (code > 0x7F) break;
-    mem[ptr + offset] = code;
-  }
-  if (offset !== len) {
-    if (offset !== 0) {
-      arg = arg.slice(offset);
+    for (; offset < len; offset++) {
+        const code = arg.charCodeAt(offset);
+        if (code > 0x7F) break;
+        mem[ptr + offset] = code;
+    }
+    if (offset !== len) {
+        if (offset !== 0) {
+            arg = arg.slice(offset);
+        }
+        ptr = realloc(ptr, len, len = offset + arg.length * 3, 1) >>> 0;
+        const view = getUint8ArrayMemory0().subarray(ptr + offset, ptr + len);
+        const ret = cachedTextEncoder.encodeInto(arg, view);
+
+        offset += ret.written;
+        ptr = realloc(ptr, len, offset, 1) >>> 0;
     }
-    ptr = realloc(ptr, len, len = offset + arg.length * 3, 1) >>> 0;
-    const view = getUint8ArrayMemory0().subarray(ptr + offset, ptr + len);
-    const ret = cachedTextEncoder.encodeInto(arg, view);
-
-    offset += ret.written;
-    ptr = realloc(ptr, len, offset, 1) >>> 0;
-  }
 
-  WASM_VECTOR_LEN = offset;
-  return ptr;
+    WASM_VECTOR_LEN = offset;
+    return ptr;
 }
 
-let cachedTextDecoder = new TextDecoder("utf-8", {
-  ignoreBOM: true,
-  fatal: true,
-});
+let cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
 cachedTextDecoder.decode();
 const MAX_SAFARI_DECODE_BYTES = 2146435072;
 let numBytesDecoded = 0;
 function decodeText(ptr, len) {
-  numBytesDecoded += len;
-  if (numBytesDecoded >= MAX_SAFARI_DECODE_BYTES) {
-    cachedTextDecoder = new TextDecoder("utf-8", {
-      ignoreBOM: true,
-      fatal: true,
-    });
-    cachedTextDecoder.decode();
-    numBytesDecoded = len;
-  }
-  return cachedTextDecoder.decode(
-    getUint8ArrayMemory0().subarray(ptr, ptr + len),
-  );
+    numBytesDecoded += len;
+    if (numBytesDecoded >= MAX_SAFARI_DECODE_BYTES) {
+        cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
+        cachedTextDecoder.decode();
+        numBytesDecoded = len;
+    }
+    return cachedTextDecoder.decode(getUint8ArrayMemory0().subarray(ptr, ptr + len));
 }
 
 const cachedTextEncoder = new TextEncoder();
 
-if (!("encodeInto" in cachedTextEncoder)) {
-  cachedTextEncoder.encodeInto = function (arg, view) {
-    const buf = cachedTextEncoder.encode(arg);
-    view.set(buf);
-    return {
-      read: arg.length,
-      written: buf.length,
-    };
-  };
+if (!('encodeInto' in cachedTextEncoder)) {
+    cachedTextEncoder.encodeInto = function (arg, view) {
+        const buf = cachedTextEncoder.encode(arg);
+        view.set(buf);
+        return {
+            read: arg.length,
+            written: buf.length
+        };
+    }
 }
 
 let WASM_VECTOR_LEN = 0;
 
+/**
+ * @param {string} html
+ * @returns {string}
+ */
+export function html_to_markdown(html) {
+    let deferred2_0;
+    let deferred2_1;
+    try {
+        const ptr0 = passStringToWasm0(html, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.html_to_markdown(ptr0, len0);
+        deferred2_0 = ret[0];
+        deferred2_1 = ret[1];
+        return getStringFromWasm0(ret[0], ret[1]);
+    } finally {
+        wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+    }
+}
+
+/**
+ * # Panics
+ *
+ * Will panic if unable to parse options
+ * @param {string} html
+ * @param {any} options
+ * @returns {string}
+ */
+export function html_to_plaintext(html, options) {
+    let deferred2_0;
+    let deferred2_1;
+    try {
+        const ptr0 = passStringToWasm0(html, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.html_to_plaintext(ptr0, len0, options);
+        deferred2_0 = ret[0];
+        deferred2_1 = ret[1];
+        return getStringFromWasm0(ret[0], ret[1]);
+    } finally {
+        wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+    }
+}
+
 /**
  * # Panics
  *
@@ -193,14 +235,10 @@ let WASM_VECTOR_LEN = 0;
  * @returns {any}
  */
 export function markdown_to_html(markdown, options) {
-  const ptr0 = passStringToWasm0(
-    markdown,
-    wasm.__wbindgen_malloc,
-    wasm.__wbindgen_realloc,
-  );
-  const len0 = WASM_VECTOR_LEN;
-  const ret = wasm.markdown_to_html(ptr0, len0, options);
-  return ret;
+    const ptr0 = passStringToWasm0(markdown, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.markdown_to_html(ptr0, len0, options);
+    return ret;
 }
 
 /**
@@ -211,23 +249,57 @@ export function markdown_to_html(markdown, options) {
  * @param {any} options
  * @returns {string}
  */
-export function markdown_to_plaintext(markdown, options) {
-  let deferred2_0;
-  let deferred2_1;
-  try {
-    const ptr0 = passStringToWasm0(
-      markdown,
-      wasm.__wbindgen_malloc,
-      wasm.__wbindgen_realloc,
-    );
+export function markdown_to_markdown(markdown, options) {
+    let deferred2_0;
+    let deferred2_1;
+    try {
+        const ptr0 = passStringToWasm0(markdown, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.markdown_to_markdown(ptr0, len0, options);
+        deferred2_0 = ret[0];
+        deferred2_1 = ret[1];
+        return getStringFromWasm0(ret[0], ret[1]);
+    } finally {
+        wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+    }
+}
+
+/**
+ * # Panics
+ *
+ * Will panic if unable to parse options
+ * @param {string} markdown
+ * @param {any} options
+ * @returns {any}
+ */
+export function markdown_to_mdast(markdown, options) {
+    const ptr0 = passStringToWasm0(markdown, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
     const len0 = WASM_VECTOR_LEN;
-    const ret = wasm.markdown_to_plaintext(ptr0, len0, options);
-    deferred2_0 = ret[0];
-    deferred2_1 = ret[1];
-    return getStringFromWasm0(ret[0], ret[1]);
-  } finally {
-    wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
-  }
+    const ret = wasm.markdown_to_mdast(ptr0, len0, options);
+    return ret;
+}
+
+/**
+ * # Panics
+ *
+ * Will panic if unable to parse options
+ * @param {string} markdown
+ * @param {any} options
+ * @returns {string}
+ */
+export function markdown_to_plaintext(markdown, options) {
+    let deferred2_0;
+    let deferred2_1;
+    try {
+        const ptr0 = passStringToWasm0(markdown, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.markdown_to_plaintext(ptr0, len0, options);
+        deferred2_0 = ret[0];
+        deferred2_1 = ret[1];
+        return getStringFromWasm0(ret[0], ret[1]);
+    } finally {
+        wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+    }
 }
 
 /**
@@ -235,175 +307,259 @@ export function markdown_to_plaintext(markdown, options) {
  * @returns {string}
  */
 export function mjml_to_html(mjml) {
-  let deferred2_0;
-  let deferred2_1;
-  try {
-    const ptr0 = passStringToWasm0(
-      mjml,
-      wasm.__wbindgen_malloc,
-      wasm.__wbindgen_realloc,
-    );
-    const len0 = WASM_VECTOR_LEN;
-    const ret = wasm.mjml_to_html(ptr0, len0);
-    deferred2_0 = ret[0];
-    deferred2_1 = ret[1];
-    return getStringFromWasm0(ret[0], ret[1]);
-  } finally {
-    wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
-  }
+    let deferred2_0;
+    let deferred2_1;
+    try {
+        const ptr0 = passStringToWasm0(mjml, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.mjml_to_html(ptr0, len0);
+        deferred2_0 = ret[0];
+        deferred2_1 = ret[1];
+        return getStringFromWasm0(ret[0], ret[1]);
+    } finally {
+        wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+    }
 }
 
 export function __wbg_Error_52673b7de5a0ca89(arg0, arg1) {
-  const ret = Error(getStringFromWasm0(arg0, arg1));
-  return ret;
-}
+    const ret = Error(getStringFromWasm0(arg0, arg1));
+    return ret;
+};
+
+export function __wbg_Number_2d1dcfcf4ec51736(arg0) {
+    const ret = Number(arg0);
+    return ret;
+};
+
+export function __wbg___wbindgen_bigint_get_as_i64_6e32f5e6aff02e1d(arg0, arg1) {
+    const v = arg1;
+    const ret = typeof(v) === 'bigint' ? v : undefined;
+    getDataViewMemory0().setBigInt64(arg0 + 8 * 1, isLikeNone(ret) ? BigInt(0) : ret, true);
+    getDataViewMemory0().setInt32(arg0 + 4 * 0, !isLikeNone(ret), true);
+};
 
 export function __wbg___wbindgen_boolean_get_dea25b33882b895b(arg0) {
-  const v = arg0;
-  const ret = typeof v === "boolean" ? v : undefined;
-  return isLikeNone(ret) ? 0xFFFFFF : ret ? 1 : 0;
-}
+    const v = arg0;
+    const ret = typeof(v) === 'boolean' ? v : undefined;
+    return isLikeNone(ret) ? 0xFFFFFF : ret ? 1 : 0;
+};
 
 export function __wbg___wbindgen_debug_string_adfb662ae34724b6(arg0, arg1) {
-  const ret = debugString(arg1);
-  const ptr1 = passStringToWasm0(
-    ret,
-    wasm.__wbindgen_malloc,
-    wasm.__wbindgen_realloc,
-  );
-  const len1 = WASM_VECTOR_LEN;
-  getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
-  getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
-}
+    const ret = debugString(arg1);
+    const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len1 = WASM_VECTOR_LEN;
+    getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
+    getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
+};
 
 export function __wbg___wbindgen_in_0d3e1e8f0c669317(arg0, arg1) {
-  const ret = arg0 in arg1;
-  return ret;
-}
+    const ret = arg0 in arg1;
+    return ret;
+};
+
+export function __wbg___wbindgen_is_bigint_0e1a2e3f55cfae27(arg0) {
+    const ret = typeof(arg0) === 'bigint';
+    return ret;
+};
+
+export function __wbg___wbindgen_is_function_8d400b8b1af978cd(arg0) {
+    const ret = typeof(arg0) === 'function';
+    return ret;
+};
 
 export function __wbg___wbindgen_is_object_ce774f3490692386(arg0) {
-  const val = arg0;
-  const ret = typeof val === "object" && val !== null;
-  return ret;
-}
+    const val = arg0;
+    const ret = typeof(val) === 'object' && val !== null;
+    return ret;
+};
+
+export function __wbg___wbindgen_is_string_704ef9c8fc131030(arg0) {
+    const ret = typeof(arg0) === 'string';
+    return ret;
+};
 
 export function __wbg___wbindgen_is_undefined_f6b95eab589e0269(arg0) {
-  const ret = arg0 === undefined;
-  return ret;
-}
+    const ret = arg0 === undefined;
+    return ret;
+};
+
+export function __wbg___wbindgen_jsval_eq_b6101cc9cef1fe36(arg0, arg1) {
+    const ret = arg0 === arg1;
+    return ret;
+};
 
 export function __wbg___wbindgen_jsval_loose_eq_766057600fdd1b0d(arg0, arg1) {
-  const ret = arg0 == arg1;
-  return ret;
-}
+    const ret = arg0 == arg1;
+    return ret;
+};
 
 export function __wbg___wbindgen_number_get_9619185a74197f95(arg0, arg1) {
-  const obj = arg1;
-  const ret = typeof obj === "number" ? obj : undefined;
-  getDataViewMemory0().setFloat64(
-    arg0 + 8 * 1,
-    isLikeNone(ret) ? 0 : ret,
-    true,
-  );
-  getDataViewMemory0().setInt32(arg0 + 4 * 0, !isLikeNone(ret), true);
-}
+    const obj = arg1;
+    const ret = typeof(obj) === 'number' ? obj : undefined;
+    getDataViewMemory0().setFloat64(arg0 + 8 * 1, isLikeNone(ret) ? 0 : ret, true);
+    getDataViewMemory0().setInt32(arg0 + 4 * 0, !isLikeNone(ret), true);
+};
 
 export function __wbg___wbindgen_string_get_a2a31e16edf96e42(arg0, arg1) {
-  const obj = arg1;
-  const ret = typeof obj === "string" ? obj : undefined;
-  var ptr1 = isLikeNone(ret)
-    ? 0
-    : passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-  var len1 = WASM_VECTOR_LEN;
-  getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
-  getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
-}
+    const obj = arg1;
+    const ret = typeof(obj) === 'string' ? obj : undefined;
+    var ptr1 = isLikeNone(ret) ? 0 : passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    var len1 = WASM_VECTOR_LEN;
+    getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
+    getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
+};
 
 export function __wbg___wbindgen_throw_dd24417ed36fc46e(arg0, arg1) {
-  throw new Error(getStringFromWasm0(arg0, arg1));
-}
+    throw new Error(getStringFromWasm0(arg0, arg1));
+};
+
+export function __wbg_call_abb4ff46ce38be40() { return handleError(function (arg0, arg1) {
+    const ret = arg0.call(arg1);
+    return ret;
+}, arguments) };
+
+export function __wbg_done_62ea16af4ce34b24(arg0) {
+    const ret = arg0.done;
+    return ret;
+};
+
+export function __wbg_entries_83c79938054e065f(arg0) {
+    const ret = Object.entries(arg0);
+    return ret;
+};
+
+export function __wbg_get_6b7bd52aca3f9671(arg0, arg1) {
+    const ret = arg0[arg1 >>> 0];
+    return ret;
+};
+
+export function __wbg_get_af9dab7e9603ea93() { return handleError(function (arg0, arg1) {
+    const ret = Reflect.get(arg0, arg1);
+    return ret;
+}, arguments) };
 
 export function __wbg_get_with_ref_key_1dc361bd10053bfe(arg0, arg1) {
-  const ret = arg0[arg1];
-  return ret;
-}
+    const ret = arg0[arg1];
+    return ret;
+};
 
 export function __wbg_instanceof_ArrayBuffer_f3320d2419cd0355(arg0) {
-  let result;
-  try {
-    result = arg0 instanceof ArrayBuffer;
-  } catch (_) {
-    result = false;
-  }
-  const ret = result;
-  return ret;
-}
+    let result;
+    try {
+        result = arg0 instanceof ArrayBuffer;
+    } catch (_) {
+        result = false;
+    }
+    const ret = result;
+    return ret;
+};
 
 export function __wbg_instanceof_Uint8Array_da54ccc9d3e09434(arg0) {
-  let result;
-  try {
-    result = arg0 instanceof Uint8Array;
-  } catch (_) {
-    result = false;
-  }
-  const ret = result;
-  return ret;
-}
+    let result;
+    try {
+        result = arg0 instanceof Uint8Array;
+    } catch (_) {
+        result = false;
+    }
+    const ret = result;
+    return ret;
+};
+
+export function __wbg_isArray_51fd9e6422c0a395(arg0) {
+    const ret = Array.isArray(arg0);
+    return ret;
+};
+
+export function __wbg_isSafeInteger_ae7d3f054d55fa16(arg0) {
+    const ret = Number.isSafeInteger(arg0);
+    return ret;
+};
+
+export function __wbg_iterator_27b7c8b35ab3e86b() {
+    const ret = Symbol.iterator;
+    return ret;
+};
 
 export function __wbg_length_22ac23eaec9d8053(arg0) {
-  const ret = arg0.length;
-  return ret;
-}
+    const ret = arg0.length;
+    return ret;
+};
+
+export function __wbg_length_d45040a40c570362(arg0) {
+    const ret = arg0.length;
+    return ret;
+};
 
 export function __wbg_log_65e7d76ac320180a(arg0, arg1) {
-  console.log(getStringFromWasm0(arg0, arg1));
-}
+    console.log(getStringFromWasm0(arg0, arg1));
+};
 
 export function __wbg_new_1ba21ce319a06297() {
-  const ret = new Object();
-  return ret;
-}
+    const ret = new Object();
+    return ret;
+};
 
 export function __wbg_new_25f239778d6112b9() {
-  const ret = new Array();
-  return ret;
-}
+    const ret = new Array();
+    return ret;
+};
 
 export function __wbg_new_6421f6084cc5bc5a(arg0) {
-  const ret = new Uint8Array(arg0);
-  return ret;
-}
+    const ret = new Uint8Array(arg0);
+    return ret;
+};
+
+export function __wbg_next_138a17bbf04e926c(arg0) {
+    const ret = arg0.next;
+    return ret;
+};
+
+export function __wbg_next_3cfe5c0fe2a4cc53() { return handleError(function (arg0) {
+    const ret = arg0.next();
+    return ret;
+}, arguments) };
 
 export function __wbg_prototypesetcall_dfe9b766cdc1f1fd(arg0, arg1, arg2) {
-  Uint8Array.prototype.set.call(getArrayU8FromWasm0(arg0, arg1), arg2);
-}
+    Uint8Array.prototype.set.call(getArrayU8FromWasm0(arg0, arg1), arg2);
+};
 
 export function __wbg_set_3f1d0b984ed272ed(arg0, arg1, arg2) {
-  arg0[arg1] = arg2;
-}
+    arg0[arg1] = arg2;
+};
 
 export function __wbg_set_7df433eea03a5c14(arg0, arg1, arg2) {
-  arg0[arg1 >>> 0] = arg2;
-}
+    arg0[arg1 >>> 0] = arg2;
+};
+
+export function __wbg_value_57b7b035e117f7ee(arg0) {
+    const ret = arg0.value;
+    return ret;
+};
 
 export function __wbindgen_cast_2241b6af4c4b2941(arg0, arg1) {
-  // Cast intrinsic for `Ref(String) -> Externref`.
-  const ret = getStringFromWasm0(arg0, arg1);
-  return ret;
-}
+    // Cast intrinsic for `Ref(String) -> Externref`.
+    const ret = getStringFromWasm0(arg0, arg1);
+    return ret;
+};
+
+export function __wbindgen_cast_4625c577ab2ec9ee(arg0) {
+    // Cast intrinsic for `U64 -> Externref`.
+    const ret = BigInt.asUintN(64, arg0);
+    return ret;
+};
 
 export function __wbindgen_cast_d6cd19b81560fd6e(arg0) {
-  // Cast intrinsic for `F64 -> Externref`.
-  const ret = arg0;
-  return ret;
-}
+    // Cast intrinsic for `F64 -> Externref`.
+    const ret = arg0;
+    return ret;
+};
 
 export function __wbindgen_init_externref_table() {
-  const table = wasm.__wbindgen_externrefs;
-  const offset = table.grow(4);
-  table.set(0, undefined);
-  table.set(offset + 0, undefined);
-  table.set(offset + 1, null);
-  table.set(offset + 2, true);
-  table.set(offset + 3, false);
-}
+    const table = wasm.__wbindgen_externrefs;
+    const offset = table.grow(4);
+    table.set(0, undefined);
+    table.set(offset + 0, undefined);
+    table.set(offset + 1, null);
+    table.set(offset + 2, true);
+    table.set(offset + 3, false);
+};
@@ -4,7 +4,7 @@
 // deno-fmt-ignore-file
 // @ts-self-types="./parsedown.d.ts"
 
-// source-hash: 41462313bf082b9dd292058cfbdeafad67dd36ee
+// source-hash: afd3761ef3bfd5ae490304a146ca70d5834882d4
 import * as wasm from "./parsedown.wasm";
 export * from "./parsedown.internal.js";
 import { __wbg_set_wasm } from "./parsedown.internal.js";
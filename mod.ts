@@ -3,16 +3,73 @@
  * examples.
  */
 
-import { instantiate } from "./lib/parsedown.generated.js";
+import {
+  html_to_markdown,
+  html_to_plaintext,
+  markdown_to_html,
+  markdown_to_markdown,
+  markdown_to_mdast,
+  markdown_to_plaintext,
+  mjml_to_html,
+} from "./lib/parsedown.js";
+
+interface Heading {
+  heading: string;
+  id: string;
+  level: number;
+}
+
+interface Excerpt {
+  html: string;
+  plaintext: string;
+
+  /** Single-line plaintext, shortened for `<meta name="description">` */
+  description: string;
+}
+
+interface Link {
+  href: string;
+  text: string;
+  kind: "internal" | "external" | "fragment";
+
+  /** Line in the source Markdown, where known */
+  line?: number;
+}
+
+interface Image {
+  src: string;
+  alt?: string;
+  title?: string;
+}
+
+interface Diagnostic {
+  kind: "unknown_page" | "unknown_fragment" | "redirected" | "unchecked";
+  href: string;
+  message: string;
+  line?: number;
+}
+
+interface CrossReference {
+  kind: "wiki_link" | "mention" | "hashtag";
+
+  /** Page name, username or topic, as written */
+  target: string;
+  href: string;
+}
 
 interface MarkdownToHtmlOKOutput {
   errors?: never;
-  headings: { heading: string; id: string }[];
+  headings: Heading[];
   html: string;
   statistics: {
     reading_time: number;
     word_count: number;
   };
+  excerpt: Excerpt;
+  links: Link[];
+  images: Image[];
+  diagnostics: Diagnostic[];
+  cross_references: CrossReference[];
 }
 
 interface MarkdownToHtmlErrorOutput {
@@ -20,15 +77,185 @@ interface MarkdownToHtmlErrorOutput {
   headings?: never;
   html?: never;
   statistics?: never;
+  excerpt?: never;
+  links?: never;
+  images?: never;
+  diagnostics?: never;
+  cross_references?: never;
 }
 
-interface MarkdownToHtmlOptions {
+interface MarkdownOptions {
   canonicalRootUrl?: string;
-  enableSmartPunctuation?: string;
+  enableSmartPunctuation?: boolean;
+
+  /** BCP 47 language tag, like `de-CH`, for quotation marks, spacing and hyphenation */
+  locale?: string;
+  appendQueryParameters?: { domain: string; parameters: [string, string][] }[];
+
+  /** Query parameter names, or `utm_*` style prefixes, removed from links */
+  stripQueryParameters?: string[];
+
+  /** URL templates, like `/wiki/{slug}`, for `[[wiki links]]`, `@mentions` and `#hashtags` */
+  wikiLinkTemplate?: string;
+  mentionTemplate?: string;
+  hashtagTemplate?: string;
+
+  /** Parse GitHub Flavored Markdown tables and `~~strikethrough~~` */
+  enableGfm?: boolean;
+  enableAbbreviations?: boolean;
+  enableEmojiShortcodes?: boolean;
+  dropEmojiFromSlugs?: boolean;
+  headingLevelOffset?: number;
+  headingOnePolicy?: "allow" | "demote" | "reject";
+}
+
+interface MarkdownToHtmlOptions extends MarkdownOptions {
   searchTerm?: string;
+  internalDomains?: string[];
+  sponsoredDomains?: string[];
+  ugcDomains?: string[];
+  externalLinksNewTab?: boolean;
+
+  /** Site paths, each with its element ids, or `null` to skip fragment checks */
+  knownPages?: Record<string, string[] | null>;
+  redirects?: Record<string, string>;
+
+  /** Path of the page being processed, like `/blog/post/`, for checking relative links */
+  pagePath?: string;
+  enableAutolink?: boolean;
+  obfuscateEmail?: boolean;
+  glossary?: { term: string; definition?: string; url?: string }[];
+  nonBreakingSpaces?: boolean;
+  joinNumberUnits?: boolean;
+  joinSingleLetterWords?: boolean;
+  joinWidows?: boolean;
+  hyphenate?: boolean;
+  hyphenationMinWordLength?: number;
+  accessibleEmoji?: boolean;
+  wrapSections?: boolean;
+  excerptWords?: number;
+  descriptionLength?: number;
+}
+
+interface PlaintextOptions {
+  /** Line length for wrapping, with `0` leaving lines unwrapped */
+  lineLength?: number;
+  linkStyle?: "inline" | "reference" | "section-reference";
+  headingStyle?: "plain" | "setext" | "uppercase";
+  emphasisStyle?: "none" | "asterisk" | "underscore";
+  labelImages?: boolean;
+
+  /** Inline HTML elements left out, defaults to `tool-tip` */
+  ignoreTags?: string[];
+  htmlElementRules?: {
+    tag: string;
+    action: "drop" | "keep-text" | "attribute";
+    attribute?: string;
+  }[];
 }
 
-type MarkdownToPlaintextOptions = Omit<MarkdownToHtmlOptions, "searchTerm">;
+type MarkdownToPlaintextOptions = MarkdownOptions & PlaintextOptions;
+
+type HtmlToPlaintextOptions =
+  & Pick<MarkdownOptions, "canonicalRootUrl">
+  & PlaintextOptions;
+
+type MarkdownToMarkdownOptions =
+  & Pick<MarkdownOptions, "enableGfm" | "wikiLinkTemplate">
+  & Pick<PlaintextOptions, "lineLength">;
+
+type MarkdownToMdastOptions = Pick<
+  MarkdownOptions,
+  | "dropEmojiFromSlugs"
+  | "enableGfm"
+  | "headingLevelOffset"
+  | "headingOnePolicy"
+  | "locale"
+  | "wikiLinkTemplate"
+>;
+
+interface MdastPoint {
+  line: number;
+  column: number;
+  offset: number;
+}
+
+interface MdastPosition {
+  start: MdastPoint;
+  end: MdastPoint;
+}
+
+/** Markdown syntax tree node, in the [mdast](https://github.com/syntax-tree/mdast) shape */
+type MdastNode =
+  & { position: MdastPosition }
+  & (
+    | {
+      type:
+        | "root"
+        | "paragraph"
+        | "blockquote"
+        | "tableRow"
+        | "tableCell"
+        | "emphasis"
+        | "strong"
+        | "delete";
+      children: MdastNode[];
+    }
+    | {
+      type: "heading";
+      depth: number;
+      children: MdastNode[];
+      data: { id: string; hProperties: { id: string } };
+    }
+    | { type: "thematicBreak" | "break" }
+    | {
+      type: "list";
+      ordered: boolean;
+      start: number | null;
+      spread: boolean;
+      children: MdastNode[];
+    }
+    | {
+      type: "listItem";
+      spread: boolean;
+      checked: boolean | null;
+      children: MdastNode[];
+    }
+    | {
+      type: "code";
+      lang: string | null;
+      meta: string | null;
+      value: string;
+    }
+    | { type: "html" | "text" | "inlineCode"; value: string }
+    | {
+      type: "table";
+      align: ("left" | "right" | "center" | null)[];
+      children: MdastNode[];
+    }
+    | {
+      type: "link";
+      url: string;
+      title: string | null;
+      children: MdastNode[];
+    }
+    | { type: "image"; url: string; title: string | null; alt: string }
+  );
+
+/**
+ * Options with their camelCase keys converted to the snake_case names used by the WASM module,
+ * and undefined values dropped
+ */
+function wasmOptions(options: object | undefined): Record<string, unknown> {
+  return Object.fromEntries(
+    Object.entries(options ?? {})
+      .filter(([, value]) => typeof value !== "undefined")
+      .map(([key, value]) => [
+        key.replace(/[A-Z]/g, (letter) => `_${letter.toLowerCase()}`),
+        value,
+      ]),
+  );
+}
 
 /**
  * Convert the, input, `markdown` string to HTML using a [CommonMark](https://commonmark.org/)
@@ -58,19 +285,9 @@ const markdownToHtml: (
     markdown,
     options: MarkdownToHtmlOptions | undefined,
   ): Promise<MarkdownToHtmlOKOutput | MarkdownToHtmlErrorOutput> {
-    const { markdown_to_html } = await instantiate();
-
     return markdown_to_html(markdown, {
       enable_smart_punctuation: true,
-      ...(typeof options?.canonicalRootUrl !== "undefined"
-        ? { canonical_root_url: options.canonicalRootUrl }
-        : {}),
-      ...(typeof options?.enableSmartPunctuation !== "undefined"
-        ? { enable_smart_punctuation: options.enableSmartPunctuation }
-        : {}),
-      ...(typeof options?.searchTerm !== "undefined"
-        ? { search_term: options.searchTerm }
-        : {}),
+      ...wasmOptions(options),
     });
   };
 
@@ -79,22 +296,79 @@ const markdownToHtml: (
  * RSS feed.
  *
  * @param markdown The Markdown text to parse
+ * @param {MarkdownToPlaintextOptions|undefined} [options={}] - Parse options
+ * @param {number} options.lineLength - wrap lines at this length, defaults to 72, with `0` leaving
+ *                                      lines unwrapped
  * @returns `markdown` parsed into a plaintext string
  */
 const markdownToPlaintext: (
   markdown: string,
   options?: MarkdownToPlaintextOptions,
 ) => Promise<string> = async function markdownToPlaintext(markdown, options) {
-  const { markdown_to_plaintext } = await instantiate();
-  const { canonicalRootUrl, enableSmartPunctuation } = options ?? {};
-  return markdown_to_plaintext(markdown, {
-    ...(typeof canonicalRootUrl !== "undefined"
-      ? { canonical_root_url: canonicalRootUrl }
-      : {}),
-    ...(typeof enableSmartPunctuation !== "undefined"
-      ? { enable_smart_punctuation: enableSmartPunctuation }
-      : {}),
-  });
+  return markdown_to_plaintext(markdown, wasmOptions(options));
+};
+
+/**
+ * Convert the, input, `html` fragment to plaintext, laid out like `markdownToPlaintext` output, to
+ * use, for example, with HTML from a CMS in a broadcast email.
+ *
+ * @param html The HTML fragment to convert
+ * @param {HtmlToPlaintextOptions|undefined} [options={}] - Conversion options
+ * @param {string} options.canonicalRootUrl - if included, relative link URLs gain this value as a
+ *                                            prefix
+ * @returns `html` converted to a plaintext string
+ */
+const htmlToPlaintext: (
+  html: string,
+  options?: HtmlToPlaintextOptions,
+) => Promise<string> = async function htmlToPlaintext(html, options) {
+  return html_to_plaintext(html, wasmOptions(options));
+};
+
+/**
+ * Convert the, input, `html` fragment to CommonMark Markdown, with GFM tables and strikethrough.
+ * Elements with no Markdown counterpart are kept as HTML.  Pass `enableGfm` when converting the
+ * output back to HTML.
+ *
+ * @param html The HTML fragment to convert
+ * @returns `html` converted to a Markdown string
+ */
+const htmlToMarkdown: (html: string) => Promise<string> =
+  async function htmlToMarkdown(html) {
+    return html_to_markdown(html);
+  };
+
+/**
+ * Re-write the, input, `markdown` string in a consistent style: ATX headings, `-` bullets, fenced
+ * code blocks, padded tables, wrapped paragraphs and link reference definitions collected at the
+ * end.
+ *
+ * @param markdown The Markdown text to format
+ * @param {MarkdownToMarkdownOptions|undefined} [options={}] - Format options
+ * @param {number} options.lineLength - wrap paragraphs at this length, defaults to 72, with `0`
+ *                                      leaving lines unwrapped
+ * @returns `markdown` re-written as a Markdown string
+ */
+const markdownToMarkdown: (
+  markdown: string,
+  options?: MarkdownToMarkdownOptions,
+) => Promise<string> = async function markdownToMarkdown(markdown, options) {
+  return markdown_to_markdown(markdown, wasmOptions(options));
+};
+
+/**
+ * Parse the, input, `markdown` string into an [mdast](https://github.com/syntax-tree/mdast)
+ * syntax tree, with source positions and heading ids, for use with unified and remark tooling.
+ *
+ * @param markdown The Markdown text to parse
+ * @param {MarkdownToMdastOptions|undefined} [options={}] - Parse options
+ * @returns {Promise<MdastNode>} `markdown` parsed into an mdast `root` node
+ */
+const markdownToMdast: (
+  markdown: string,
+  options?: MarkdownToMdastOptions,
+) => Promise<MdastNode> = async function markdownToMdast(markdown, options) {
+  return markdown_to_mdast(markdown, wasmOptions(options));
 };
 
 /**
@@ -106,8 +380,33 @@ const markdownToPlaintext: (
 const mjmlToHtml: (mjml: string) => Promise<string> = async function mjmlToHtml(
   mjml,
 ) {
-  const { mjml_to_html } = await instantiate();
   return mjml_to_html(mjml);
 };
 
-export { markdownToHtml, markdownToPlaintext, mjmlToHtml };
+export {
+  htmlToMarkdown,
+  htmlToPlaintext,
+  markdownToHtml,
+  markdownToMarkdown,
+  markdownToMdast,
+  markdownToPlaintext,
+  mjmlToHtml,
+};
+export type {
+  CrossReference,
+  Diagnostic,
+  Excerpt,
+  Heading,
+  HtmlToPlaintextOptions,
+  Image,
+  Link,
+  MarkdownToHtmlErrorOutput,
+  MarkdownToHtmlOKOutput,
+  MarkdownToHtmlOptions,
+  MarkdownToMarkdownOptions,
+  MarkdownToMdastOptions,
+  MarkdownToPlaintextOptions,
+  MdastNode,
+  MdastPoint,
+  MdastPosition,
+};
@@ -1,5 +1,13 @@
 import { assert, assertEquals } from "@std/assert";
-import { markdownToHtml, markdownToPlaintext, mjmlToHtml } from "./mod.ts";
+import {
+  htmlToMarkdown,
+  htmlToPlaintext,
+  markdownToHtml,
+  markdownToMarkdown,
+  markdownToMdast,
+  markdownToPlaintext,
+  mjmlToHtml,
+} from "./mod.ts";
 
 Deno.test("it parses markdown to html", async () => {
   // arrange
@@ -28,6 +36,7 @@ Deno.test("it parses markdown to html", async () => {
   assertEquals(headings![0], {
     heading: "👋🏽 Hello You",
     id: "wave-hello-you",
+    level: 2,
   });
   assertEquals(
     html,
@@ -80,7 +89,6 @@ Deno.test("it parses markdown to plain text", async () => {
     `👋🏽 Hello You
 
 - alpha
-
 - beta
 
 Example Link (https://example.com/)
@@ -88,6 +96,143 @@ Example Link (https://example.com/)
   );
 });
 
+Deno.test("it wraps plaintext lines and lists link references", async () => {
+  // arrange
+  const markdown =
+    "A sentence with [a link](https://example.com/) that goes on for a while.";
+
+  // act
+  const plaintext = await markdownToPlaintext(markdown, {
+    lineLength: 30,
+    linkStyle: "reference",
+  });
+
+  // assert
+  assertEquals(
+    plaintext,
+    `A sentence with a link [1]
+that goes on for a while.
+
+[1] https://example.com/
+`,
+  );
+});
+
+Deno.test("it converts html to plain text", async () => {
+  // arrange
+  const html =
+    '<h2>Hello You</h2><p>Read the <a href="/docs">docs</a> <tool-tip>hidden</tool-tip>now.</p><ul><li>alpha</li><li>beta</li></ul>';
+
+  // act
+  const plaintext = await htmlToPlaintext(html, {
+    canonicalRootUrl: "https://example.com",
+    headingStyle: "uppercase",
+  });
+
+  // assert
+  assert(typeof htmlToPlaintext === "function");
+  assertEquals(
+    plaintext,
+    `HELLO YOU
+
+Read the docs (https://example.com/docs) now.
+
+- alpha
+- beta
+`,
+  );
+});
+
+Deno.test("it converts html to markdown", async () => {
+  // arrange
+  const html =
+    '<h2>Hello You</h2><p>Some <strong>bold</strong> and <a href="https://example.com/">a link</a>.</p><ul><li>alpha</li><li>beta</li></ul>';
+
+  // act
+  const markdown = await htmlToMarkdown(html);
+
+  // assert
+  assert(typeof htmlToMarkdown === "function");
+  assertEquals(
+    markdown,
+    `## Hello You
+
+Some **bold** and [a link](https://example.com/).
+
+- alpha
+- beta
+`,
+  );
+});
+
+Deno.test("it formats markdown", async () => {
+  // arrange
+  const markdown = `Hello You
+=========
+
+* alpha
+* beta
+
+A sentence that goes on for a while and wraps.
+`;
+
+  // act
+  const formatted = await markdownToMarkdown(markdown, { lineLength: 20 });
+
+  // assert
+  assert(typeof markdownToMarkdown === "function");
+  assertEquals(
+    formatted,
+    `# Hello You
+
+- alpha
+- beta
+
+A sentence that goes
+on for a while and
+wraps.
+`,
+  );
+});
+
+Deno.test("it parses markdown to an mdast tree", async () => {
+  // arrange
+  const markdown = "## Hello\n\nSome *text*\n";
+
+  // act
+  const tree = await markdownToMdast(markdown, {});
+
+  // assert
+  assert(typeof markdownToMdast === "function");
+  assertEquals(tree.type, "root");
+  assertEquals(tree.position, {
+    start: { line: 1, column: 1, offset: 0 },
+    end: { line: 4, column: 1, offset: 22 },
+  });
+  assert("children" in tree);
+  const [heading, paragraph] = tree.children;
+  assertEquals(heading, {
+    type: "heading",
+    depth: 2,
+    children: [
+      {
+        type: "text",
+        value: "Hello",
+        position: {
+          start: { line: 1, column: 4, offset: 3 },
+          end: { line: 1, column: 9, offset: 8 },
+        },
+      },
+    ],
+    data: { id: "hello", hProperties: { id: "hello" } },
+    position: {
+      start: { line: 1, column: 1, offset: 0 },
+      end: { line: 1, column: 9, offset: 8 },
+    },
+  });
+  assertEquals(paragraph.type, "paragraph");
+});
+
 Deno.test("it parses mjml to html", async () => {
   // arrange
   const mjml = '<mjml lang="en-GB"></mjml>';
@@ -99,7 +244,7 @@ Deno.test("it parses mjml to html", async () => {
   assert(typeof mjmlToHtml === "function");
   assertEquals(
     html,
-    `<!doctype html><html lang="en-GB" xmlns="http://www.w3.org/1999/xhtml" xmlns:v="urn:schemas-microsoft-com:vml" xmlns:o="urn:schemas-microsoft-com:office:office"><head><title></title><!--[if !mso]><!--><meta http-equiv="X-UA-Compatible" content="IE=edge"><!--<![endif]--><meta http-equiv="Content-Type" content="text/html; charset=UTF-8"><meta name="viewport" content="width=device-width, initial-scale=1">
+    `<!doctype html><html lang="en-GB" dir="auto" xmlns="http://www.w3.org/1999/xhtml" xmlns:v="urn:schemas-microsoft-com:vml" xmlns:o="urn:schemas-microsoft-com:office:office"><head><title></title><!--[if !mso]><!--><meta http-equiv="X-UA-Compatible" content="IE=edge"><!--<![endif]--><meta http-equiv="Content-Type" content="text/html; charset=UTF-8"><meta name="viewport" content="width=device-width, initial-scale=1">
 <style type="text/css">
 #outlook a { padding: 0; }
 body { margin: 0; padding: 0; -webkit-text-size-adjust: 100%; -ms-text-size-adjust: 100%; }
@@ -122,6 +267,6 @@ p { display: block; margin: 13px 0; }
 .mj-outlook-group-fix { width:100% !important; }
 </style>
 <![endif]-->
-<style type="text/css"></style></head><body></body></html>`,
+</head><body></body></html>`,
   );
 });
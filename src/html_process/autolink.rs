@@ -0,0 +1,187 @@
+use std::fmt::Write;
+
+/// Bare URL, `www.` host or email address found in text, `start` and `end` are byte offsets
+#[derive(Debug, Eq, PartialEq)]
+pub struct Autolink {
+    pub start: usize,
+    pub end: usize,
+    pub href: String,
+}
+
+/// Characters which may open a bare link without being part of it, as in `(www.example.com)`
+const LEADING_DELIMITERS: &[char] = &['(', '*', '_', '~', '"', '\'', '“', '‘'];
+
+/// Trailing punctuation which usually ends a sentence rather than the link
+const TRAILING_PUNCTUATION: &[char] = &[
+    '?', '!', '.', ',', ':', ';', '*', '_', '~', '"', '\'', '”', '’',
+];
+
+/// Removes trailing punctuation and unbalanced closing parentheses from a candidate link
+fn trim_trailing(candidate: &str) -> &str {
+    let mut result = candidate;
+    loop {
+        if let Some(value) = result.strip_suffix(TRAILING_PUNCTUATION) {
+            result = value;
+        } else if result.ends_with(')') && result.matches(')').count() > result.matches('(').count()
+        {
+            result = &result[..result.len() - 1];
+        } else {
+            return result;
+        }
+    }
+}
+
+/// `true` for a host with at least two labels, like `example.com`
+fn valid_domain(domain: &str) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    labels.len() > 1
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+fn valid_email(candidate: &str) -> bool {
+    let Some((local, domain)) = candidate.split_once('@') else {
+        return false;
+    };
+    let top_level_domain = domain.rsplit('.').next().unwrap_or_default();
+    !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".+-_".contains(c))
+        && valid_domain(domain)
+        && top_level_domain.len() > 1
+        && top_level_domain.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// `href` for `candidate` if it is a bare URL, `www.` host or email address
+fn autolink_href(candidate: &str) -> Option<String> {
+    let lowercase = candidate.to_ascii_lowercase();
+    let (href, rest) = if let Some(rest) = lowercase
+        .strip_prefix("https://")
+        .or_else(|| lowercase.strip_prefix("http://"))
+    {
+        (candidate.to_string(), rest)
+    } else if let Some(rest) = lowercase.strip_prefix("www.") {
+        (format!("https://{candidate}"), rest)
+    } else if valid_email(candidate) {
+        return Some(format!("mailto:{candidate}"));
+    } else {
+        return None;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    valid_domain(host).then_some(href)
+}
+
+/// Finds links in plain text, following the GitHub Flavored Markdown extended autolink rules
+pub fn find_autolinks(text: &str) -> Vec<Autolink> {
+    let mut result = Vec::new();
+    let mut token_start = None;
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        let boundary = c.is_whitespace() || c == '<';
+        match (token_start, boundary) {
+            (None, false) => token_start = Some(index),
+            (Some(start), true) => {
+                let token = &text[start..index];
+                let trimmed_start = token.trim_start_matches(LEADING_DELIMITERS);
+                let candidate = trim_trailing(trimmed_start);
+                if let Some(href) = autolink_href(candidate) {
+                    let start = start + token.len() - trimmed_start.len();
+                    result.push(Autolink {
+                        start,
+                        end: start + candidate.len(),
+                        href,
+                    });
+                }
+                token_start = None;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Replaces every character of `value` with a decimal HTML entity
+pub fn entity_encode(value: &str) -> String {
+    value.chars().fold(String::new(), |mut result, c| {
+        let _ = write!(result, "&#{};", u32::from(c));
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Autolink, entity_encode, find_autolinks};
+
+    #[test]
+    pub fn find_autolinks_finds_bare_urls_hosts_and_email_addresses() {
+        // arrange
+        let text =
+            "Visit https://example.com/docs?page=1, www.example.org or email ask@example.com.";
+
+        // act
+        let result = find_autolinks(text);
+
+        // assert
+        assert_eq!(
+            result,
+            vec![
+                Autolink {
+                    start: 6,
+                    end: 37,
+                    href: String::from("https://example.com/docs?page=1")
+                },
+                Autolink {
+                    start: 39,
+                    end: 54,
+                    href: String::from("https://www.example.org")
+                },
+                Autolink {
+                    start: 64,
+                    end: 79,
+                    href: String::from("mailto:ask@example.com")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn find_autolinks_trims_unbalanced_parentheses() {
+        // arrange
+        let text = "(see https://en.wikipedia.org/wiki/Rust_(programming_language))";
+
+        // act
+        let result = find_autolinks(text);
+
+        // assert
+        assert_eq!(
+            result,
+            vec![Autolink {
+                start: 5,
+                end: 62,
+                href: String::from("https://en.wikipedia.org/wiki/Rust_(programming_language)")
+            }]
+        );
+    }
+
+    #[test]
+    pub fn find_autolinks_ignores_text_without_links() {
+        // arrange
+        let text = "Version 1.5 costs £3.50, see www. or user@localhost";
+
+        // act
+        let result = find_autolinks(text);
+
+        // assert
+        assert_eq!(result, Vec::new());
+    }
+
+    #[test]
+    pub fn entity_encode_encodes_every_character() {
+        assert_eq!(entity_encode("a@b"), "&#97;&#64;&#98;");
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+
+/// Term linked, or expanded with `<abbr>`, on its first occurrence in body text
+#[derive(Debug)]
+pub struct GlossaryTerm<'a> {
+    term: &'a str,
+    definition: Option<&'a str>,
+    url: Option<&'a str>,
+}
+
+impl<'a> GlossaryTerm<'a> {
+    pub fn new(term: &'a str, definition: Option<&'a str>, url: Option<&'a str>) -> Self {
+        GlossaryTerm {
+            term,
+            definition,
+            url,
+        }
+    }
+}
+
+/// Element wrapping a glossary match, an `<abbr>` with a title or a link with an optional title
+#[derive(Debug, Eq, PartialEq)]
+pub enum GlossaryElement<'a> {
+    Abbreviation {
+        title: &'a str,
+    },
+    Link {
+        href: &'a str,
+        title: Option<&'a str>,
+    },
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct GlossaryMatch<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub element: GlossaryElement<'a>,
+}
+
+#[derive(Debug, Default)]
+pub struct Glossary<'a> {
+    terms: Vec<GlossaryTerm<'a>>,
+
+    /// Abbreviations and their expansions, wrapped in `<abbr>` wherever they occur
+    abbreviations: Vec<(&'a str, &'a str)>,
+
+    /// Matcher for terms, followed by abbreviations, rebuilt whenever either changes
+    automaton: Option<AhoCorasick>,
+}
+
+/// `true` if the match at `start..end` is not part of a longer word
+fn whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+impl<'a> Glossary<'a> {
+    pub fn terms(&mut self, value: Vec<GlossaryTerm<'a>>) -> &mut Self {
+        self.terms = value;
+        self.build_automaton();
+        self
+    }
+
+    pub fn abbreviations(&mut self, value: Vec<(&'a str, &'a str)>) -> &mut Self {
+        self.abbreviations = value;
+        self.build_automaton();
+        self
+    }
+
+    fn build_automaton(&mut self) {
+        if self.terms.is_empty() && self.abbreviations.is_empty() {
+            self.automaton = None;
+            return;
+        }
+        let patterns = self.terms.iter().map(|value| value.term).chain(
+            self.abbreviations
+                .iter()
+                .map(|(abbreviation, _)| *abbreviation),
+        );
+        self.automaton = Some(
+            AhoCorasickBuilder::new()
+                .match_kind(MatchKind::LeftmostLongest)
+                .build(patterns)
+                .expect("Glossary terms should build valid Aho-Corasick instance"),
+        );
+    }
+
+    /*
+     * Finds glossary terms and abbreviations in `text`.  `used_terms` holds indices of glossary
+     * terms already wrapped earlier in the document, which are skipped.
+     */
+    pub fn find_matches(
+        &self,
+        text: &str,
+        used_terms: &mut HashSet<usize>,
+    ) -> Vec<GlossaryMatch<'a>> {
+        let Some(ac) = &self.automaton else {
+            return Vec::new();
+        };
+        let mut result = Vec::new();
+        for term_match in ac.find_iter(text) {
+            let (start, end) = (term_match.start(), term_match.end());
+            let index = term_match.pattern().as_usize();
+            if !whole_word(text, start, end) {
+                continue;
+            }
+            let element = if let Some(term) = self.terms.get(index) {
+                if !used_terms.insert(index) {
+                    continue;
+                }
+                match (term.url, term.definition) {
+                    (Some(href), title) => GlossaryElement::Link { href, title },
+                    (None, Some(title)) => GlossaryElement::Abbreviation { title },
+                    (None, None) => continue,
+                }
+            } else {
+                let (_, title) = self.abbreviations[index - self.terms.len()];
+                GlossaryElement::Abbreviation { title }
+            };
+            result.push(GlossaryMatch {
+                start,
+                end,
+                element,
+            });
+        }
+        result
+    }
+}
@@ -0,0 +1,155 @@
+use hypher::{Lang, hyphenate};
+
+use super::dom::{Handle, NodeData};
+
+pub(super) const SOFT_HYPHEN: char = '\u{ad}';
+
+/// Elements whose text is left unchanged, along with their descendants
+const SKIPPED_ELEMENTS: [&str; 5] = ["code", "kbd", "pre", "script", "style"];
+
+/// Soft hyphen insertion for long words in paragraph text, using the TeX hyphenation patterns
+/// embedded by `hypher`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Hyphenation {
+    language: Lang,
+    min_word_length: usize,
+}
+
+impl Hyphenation {
+    /// Hyphenation for a BCP 47 language tag, like `de-AT`, or `None` if there is no bundled
+    /// dictionary for the language
+    pub fn for_language_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        let code: [u8; 2] = language.to_ascii_lowercase().as_bytes().try_into().ok()?;
+        let language = Lang::from_iso(code)?;
+        Some(Hyphenation {
+            language,
+            min_word_length: 10,
+        })
+    }
+
+    /// Words with fewer characters are left unchanged, defaults to 10
+    pub fn min_word_length(&mut self, value: usize) -> &mut Self {
+        self.min_word_length = value;
+        self
+    }
+
+    /// Adds soft hyphens to long words in paragraphs within `node`, skipping headings, code, URLs
+    /// and email addresses
+    pub(super) fn apply(self, node: &Handle) {
+        let mut stack = vec![node.clone()];
+        while let Some(current) = stack.pop() {
+            if let NodeData::Element { ref name, .. } = current.data {
+                if SKIPPED_ELEMENTS.contains(&&*name.local) {
+                    continue;
+                }
+                if &*name.local == "p" {
+                    self.hyphenate_paragraph(&current);
+                    continue;
+                }
+            }
+            stack.extend(current.children.borrow().iter().rev().cloned());
+        }
+    }
+
+    /// Hyphenates paragraph text as a whole, so words split across inline elements, such as search
+    /// matches, are hyphenated like the full word
+    fn hyphenate_paragraph(self, node: &Handle) {
+        // text nodes in document order, with `None` marking skipped elements, which end words
+        let mut segments = Vec::new();
+        let mut stack = vec![node.clone()];
+        while let Some(current) = stack.pop() {
+            match current.data {
+                NodeData::Text { .. } => segments.push(Some(current.clone())),
+                NodeData::Element { ref name, .. } if SKIPPED_ELEMENTS.contains(&&*name.local) => {
+                    segments.push(None);
+                    continue;
+                }
+                _ => {}
+            }
+            stack.extend(current.children.borrow().iter().rev().cloned());
+        }
+
+        let mut text = String::new();
+        for segment in &segments {
+            match segment.as_ref().map(|node| &node.data) {
+                Some(NodeData::Text { contents }) => text.push_str(&contents.borrow()),
+                _ => text.push(' '),
+            }
+        }
+        let positions = self.soft_hyphen_positions(&text);
+        if positions.is_empty() {
+            return;
+        }
+
+        let mut offset = 0;
+        for segment in &segments {
+            let Some(NodeData::Text { contents }) = segment.as_ref().map(|node| &node.data) else {
+                offset += 1;
+                continue;
+            };
+            let original = contents.borrow().to_string();
+            let mut updated = String::with_capacity(original.len() + 8);
+            for (index, c) in original.chars().enumerate() {
+                if positions.binary_search(&(offset + index)).is_ok() {
+                    updated.push(SOFT_HYPHEN);
+                }
+                updated.push(c);
+            }
+            offset += original.chars().count();
+            if updated.len() != original.len() {
+                *contents.borrow_mut() = updated.into();
+            }
+        }
+    }
+
+    /// Sorted character indices in `text` where soft hyphens should be inserted
+    fn soft_hyphen_positions(self, text: &str) -> Vec<usize> {
+        let mut positions = Vec::new();
+        let mut offset = 0;
+        for token in text.split_inclusive(char::is_whitespace) {
+            let token_length = token.chars().count();
+            if token.contains("://") || token.contains("www.") || token.contains('@') {
+                offset += token_length;
+                continue;
+            }
+            let mut word = String::new();
+            let mut word_start = offset;
+            for (index, c) in token.chars().chain([' ']).enumerate() {
+                if c.is_alphabetic() {
+                    if word.is_empty() {
+                        word_start = offset + index;
+                    }
+                    word.push(c);
+                } else if !word.is_empty() {
+                    positions.extend(
+                        self.word_breaks(&word)
+                            .into_iter()
+                            .map(|position| word_start + position),
+                    );
+                    word.clear();
+                }
+            }
+            offset += token_length;
+        }
+        positions
+    }
+
+    /// Allowed breaks in `word`, as character indices, if it is long enough to hyphenate
+    fn word_breaks(self, word: &str) -> Vec<usize> {
+        if word.chars().count() < self.min_word_length {
+            return Vec::new();
+        }
+        let mut syllables: Vec<usize> = hyphenate(word, self.language)
+            .map(|syllable| syllable.chars().count())
+            .collect();
+        syllables.pop();
+        syllables
+            .into_iter()
+            .scan(0, |position, length| {
+                *position += length;
+                Some(*position)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use url::{Position, Url};
+
+use crate::url_utility::relative_url;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// Link to a site path missing from the manifest
+    UnknownPage,
+
+    /// Link to a fragment missing from the target page, or from the current document
+    UnknownFragment,
+
+    /// Link rewritten through the redirect map
+    Redirected,
+
+    /// Document-relative link which could not be checked, with no page path to resolve it against
+    Unchecked,
+}
+
+/// Problem, or change, found while processing a document's links
+#[derive(Clone, Debug, Eq, Serialize)]
+pub struct Diagnostic {
+    kind: DiagnosticKind,
+    href: String,
+    message: String,
+
+    /// Line in the source document, where known
+    line: Option<usize>,
+
+    /// Index, in the document links, of the link this diagnostic refers to
+    #[serde(skip)]
+    link_index: usize,
+}
+
+impl PartialEq for Diagnostic {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.href == other.href
+            && self.message == other.message
+            && self.line == other.line
+    }
+}
+
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind, href: &str, message: &str, line: Option<usize>) -> Diagnostic {
+        Diagnostic {
+            kind,
+            href: href.into(),
+            message: message.into(),
+            line,
+            link_index: 0,
+        }
+    }
+
+    pub(super) fn for_link(mut self, link_index: usize) -> Diagnostic {
+        self.link_index = link_index;
+        self
+    }
+
+    pub(super) fn link_index(&self) -> usize {
+        self.link_index
+    }
+
+    pub(super) fn set_line(&mut self, line: Option<usize>) {
+        self.line = line;
+    }
+}
+
+/// Site paths, with their heading ids where known, that internal links are checked against
+#[derive(Debug, Default)]
+pub struct SiteManifest<'a> {
+    /// Known paths, mapped to the ids on that page, or `None` if fragments should not be checked
+    pages: HashMap<&'a str, Option<Vec<&'a str>>>,
+
+    /// Old paths mapped to their replacement
+    redirects: HashMap<&'a str, &'a str>,
+
+    /// Path of the page being processed, which document-relative links are resolved against
+    page_path: Option<&'a str>,
+}
+
+/// Removes any trailing slash, so `/blog/` and `/blog` match
+fn normalise_path(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        value => value,
+    }
+}
+
+/// Whether `href` is relative to the current document, like `old-slug` or `../old-slug`, rather
+/// than root-relative, absolute or a fragment
+fn document_relative(href: &str) -> bool {
+    !href.starts_with(['/', '#']) && relative_url(href)
+}
+
+impl<'a> SiteManifest<'a> {
+    pub fn pages(&mut self, value: HashMap<&'a str, Option<Vec<&'a str>>>) -> &mut Self {
+        self.pages = value;
+        self
+    }
+
+    pub fn redirects(&mut self, value: HashMap<&'a str, &'a str>) -> &mut Self {
+        self.redirects = value;
+        self
+    }
+
+    /// Path of the page being processed, like `/blog/post/`, so document-relative links can be
+    /// checked
+    pub fn page_path(&mut self, value: Option<&'a str>) -> &mut Self {
+        self.page_path = value;
+        self
+    }
+
+    /// `href` resolved to an absolute URL, with root and document-relative links resolved
+    /// against the page path on a placeholder host.  Returns `None` for document-relative links
+    /// when there is no page path.
+    fn resolve(&self, href: &str) -> Option<Url> {
+        let base = Url::parse("http://localhost").expect("Static URL should parse");
+        if document_relative(href) {
+            base.join(self.page_path?).ok()?.join(href).ok()
+        } else if href.starts_with('/') && !href.starts_with("//") {
+            base.join(href).ok()
+        } else {
+            Url::parse(href).ok()
+        }
+    }
+
+    /// Returns `href` with its path replaced, if the path has a redirect.  The query and fragment
+    /// of `href` are kept, unless the redirect target has its own.
+    pub(super) fn redirect(&self, href: &str) -> Option<String> {
+        let url = self.resolve(href)?;
+        let target = self.redirects.get(normalise_path(url.path()))?;
+        let mut result = url.join(target).ok()?;
+        if result.query().is_none() {
+            result.set_query(url.query());
+        }
+        if result.fragment().is_none() {
+            result.set_fragment(url.fragment());
+        }
+        if relative_url(href) && relative_url(target) {
+            Some(result[Position::BeforePath..].to_string())
+        } else {
+            Some(result.to_string())
+        }
+    }
+
+    /// Checks a link to another page on the site
+    pub(super) fn check(&self, href: &str) -> Option<Diagnostic> {
+        let Some(url) = self.resolve(href) else {
+            return document_relative(href).then(|| {
+                Diagnostic::new(
+                    DiagnosticKind::Unchecked,
+                    href,
+                    &format!("Relative link {href} not checked, with no page path set"),
+                    None,
+                )
+            });
+        };
+        let path = normalise_path(url.path());
+        let fragment = url.fragment();
+        let Some(ids) = self.pages.get(path) else {
+            return Some(Diagnostic::new(
+                DiagnosticKind::UnknownPage,
+                href,
+                &format!("Link to unknown page {path}"),
+                None,
+            ));
+        };
+        match (fragment, ids) {
+            (Some(fragment), Some(ids)) if !fragment.is_empty() && !ids.contains(&fragment) => {
+                Some(Diagnostic::new(
+                    DiagnosticKind::UnknownFragment,
+                    href,
+                    &format!("Link to missing fragment #{fragment} on {path}"),
+                    None,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
@@ -25,42 +25,208 @@
 #[cfg(test)]
 mod tests;
 
-mod dom;
+mod autolink;
+pub(crate) mod dom;
+mod glossary;
+mod hyphenation;
+mod link_validation;
+mod non_breaking_space;
+mod section;
+mod serialize;
 
 use std::{
     cell::RefCell,
+    collections::HashSet,
     fmt::{self, Display},
     mem,
     rc::Rc,
 };
 
 use aho_corasick::AhoCorasickBuilder;
+use autolink::find_autolinks;
 use dom::{Handle, Node, NodeData, RcDom, SerializableHandle};
 use html5ever::{
     Attribute, QualName, driver,
     interface::tree_builder::{AppendNode, NodeOrText, TreeSink},
     local_name, ns,
-    serialize::{SerializeOpts, serialize},
+    serialize::{Serialize as _, TraversalScope},
     tendril::{StrTendril, TendrilSink, format_tendril},
 };
 
-use crate::url_utility::relative_url;
+use glossary::GlossaryElement;
+pub use glossary::{Glossary, GlossaryTerm};
+pub use hyphenation::Hyphenation;
+pub use link_validation::{Diagnostic, DiagnosticKind, SiteManifest};
+pub use non_breaking_space::NonBreakingSpaceRules;
+use serde::Serialize;
+use serialize::DocumentSerializer;
 
+use crate::{
+    text::find_emoji,
+    url_utility::{UrlRewriter, relative_url, url_host, url_in_domain},
+};
+
+/// How an anchor `href` is treated when adding `target` and `rel` attributes
+#[derive(Debug, Eq, PartialEq)]
+enum LinkClass {
+    Internal,
+    External,
+    Sponsored,
+    UserGenerated,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Internal,
+    External,
+    Fragment,
+}
+
+/// Anchor found in the processed document
+#[derive(Clone, Debug, Eq, Serialize)]
+pub struct Link {
+    href: String,
+    text: String,
+    kind: LinkKind,
+
+    /// Line in the source document, where known
+    line: Option<usize>,
+
+    /// `href` before any canonical root URL or query parameter rewriting
+    #[serde(skip)]
+    source_href: String,
+}
+
+#[cfg(test)]
+impl Link {
+    pub fn new(href: &str, text: &str, kind: LinkKind, line: Option<usize>) -> Link {
+        Link {
+            href: href.into(),
+            text: text.into(),
+            kind,
+            line,
+            source_href: href.into(),
+        }
+    }
+}
+
+impl PartialEq for Link {
+    fn eq(&self, other: &Self) -> bool {
+        self.href == other.href
+            && self.text == other.text
+            && self.kind == other.kind
+            && self.line == other.line
+    }
+}
+
+/// Image found in the processed document
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Image {
+    src: String,
+    alt: Option<String>,
+    title: Option<String>,
+}
+
+#[cfg(test)]
+impl Image {
+    pub fn new(src: &str, alt: Option<&str>, title: Option<&str>) -> Image {
+        Image {
+            src: src.into(),
+            alt: alt.map(Into::into),
+            title: title.map(Into::into),
+        }
+    }
+}
+
+/// Concatenated text of `node` and its descendants
+fn text_content(node: &Handle) -> String {
+    let mut result = String::new();
+    let mut stack = vec![node.clone()];
+    while let Some(current) = stack.pop() {
+        if let NodeData::Text { ref contents } = current.data {
+            result.push_str(&contents.borrow());
+        }
+        stack.extend(current.children.borrow().iter().rev().cloned());
+    }
+    result
+}
+
+/// Values collected while walking the DOM in `process_dom`
+#[derive(Default)]
+struct ProcessState {
+    already_matched: bool,
+    links: Vec<Link>,
+    images: Vec<Image>,
+    diagnostics: Vec<Diagnostic>,
+    element_ids: HashSet<String>,
+
+    /// Same-document fragment links, with their link index, checked once every id is known
+    fragment_links: Vec<(usize, String)>,
+
+    /// Email addresses to entity encode in the serialised output
+    obfuscated_emails: Vec<String>,
+
+    /// Indices of glossary terms already wrapped, since only first occurrences are
+    used_glossary_terms: HashSet<usize>,
+}
+
+/// `true` if `node`, or one of its ancestors, is an element named in `names`
+fn within_element(node: &Handle, names: &[&str]) -> bool {
+    let mut current = Some(node.clone());
+    while let Some(value) = current {
+        if let NodeData::Element { ref name, .. } = value.data {
+            if names.contains(&&*name.local) {
+                return true;
+            }
+        }
+        let parent = value.parent.take();
+        value.parent.set(parent.clone());
+        current = parent.and_then(|weak| weak.upgrade());
+    }
+    false
+}
+
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub struct Builder<'a> {
+    accessible_emoji: bool,
+    autolink: bool,
     canonical_root_url: Option<&'a str>,
+    glossary: Glossary<'a>,
+    hyphenation: Option<Hyphenation>,
+    internal_domains: Vec<&'a str>,
     link_rel: Option<&'a str>,
     link_target: Option<&'a str>,
+    non_breaking_spaces: Option<NonBreakingSpaceRules>,
+    obfuscate_email: bool,
     search_term: Option<&'a str>,
+    site_manifest: Option<SiteManifest<'a>>,
+    sponsored_domains: Vec<&'a str>,
+    ugc_domains: Vec<&'a str>,
+    url_rewriter: UrlRewriter<'a>,
+    wrap_sections: bool,
 }
 
 impl Default for Builder<'_> {
     fn default() -> Self {
         Builder {
+            accessible_emoji: false,
+            autolink: false,
             canonical_root_url: None,
+            glossary: Glossary::default(),
+            hyphenation: None,
+            internal_domains: Vec::new(),
             link_rel: Some("noopener noreferrer"),
             link_target: Some("_blank"),
+            non_breaking_spaces: None,
+            obfuscate_email: false,
             search_term: None,
+            site_manifest: None,
+            sponsored_domains: Vec::new(),
+            ugc_domains: Vec::new(),
+            url_rewriter: UrlRewriter::default(),
+            wrap_sections: false,
         }
     }
 }
@@ -70,21 +236,138 @@ impl<'a> Builder<'a> {
         Self::default()
     }
 
+    /// Wraps emoji in `<span role="img" aria-label="…">`, labelled with the emoji name, so screen
+    /// readers announce them consistently
+    pub fn accessible_emoji(&mut self, value: bool) -> &mut Self {
+        self.accessible_emoji = value;
+        self
+    }
+
+    /// Turns bare URLs, `www.` hosts and email addresses in text into links
+    pub fn autolink(&mut self, value: bool) -> &mut Self {
+        self.autolink = value;
+        self
+    }
+
     pub fn canonical_root_url(&mut self, value: Option<&'a str>) -> &mut Self {
         self.canonical_root_url = value;
         self
     }
 
+    /// Glossary terms and abbreviations to wrap in `<abbr>` or link, in body text outside code,
+    /// headings and existing links
+    pub fn glossary(&mut self, value: Glossary<'a>) -> &mut Self {
+        self.glossary = value;
+        self
+    }
+
+    /// Adds soft hyphens to long words in paragraph text.  Applied after search matching, and word
+    /// counts come from the Markdown source, so neither is affected.
+    pub fn hyphenation(&mut self, value: Option<Hyphenation>) -> &mut Self {
+        self.hyphenation = value;
+        self
+    }
+
+    /// Domains, including their subdomains, whose links are treated like relative links, with no
+    /// `target` or `rel` added.  The host of `canonical_root_url` is always treated as internal.
+    pub fn internal_domains(&mut self, value: Vec<&'a str>) -> &mut Self {
+        self.internal_domains = value;
+        self
+    }
+
     pub fn link_rel(&mut self, value: Option<&'a str>) -> &mut Self {
         self.link_rel = value;
         self
     }
 
+    /// `target` attribute value for external links, `None` to omit the attribute
+    pub fn link_target(&mut self, value: Option<&'a str>) -> &mut Self {
+        self.link_target = value;
+        self
+    }
+
+    /// Replaces spaces in text, outside code, with no-break spaces following `value` rules
+    pub fn non_breaking_spaces(&mut self, value: Option<NonBreakingSpaceRules>) -> &mut Self {
+        self.non_breaking_spaces = value;
+        self
+    }
+
+    /// Entity encodes `mailto:` link addresses, to hide them from simple scrapers
+    pub fn obfuscate_email(&mut self, value: bool) -> &mut Self {
+        self.obfuscate_email = value;
+        self
+    }
+
     pub fn search_term(&mut self, value: Option<&'a str>) -> &mut Self {
         self.search_term = value;
         self
     }
 
+    /// Known site pages and redirects.  When set, root-relative, internal and fragment links are
+    /// checked against it, with problems reported in `Document::diagnostics`.
+    pub fn site_manifest(&mut self, value: Option<SiteManifest<'a>>) -> &mut Self {
+        self.site_manifest = value;
+        self
+    }
+
+    /// Domains whose links get `rel="sponsored"` in addition to `link_rel`
+    pub fn sponsored_domains(&mut self, value: Vec<&'a str>) -> &mut Self {
+        self.sponsored_domains = value;
+        self
+    }
+
+    /// Domains whose links get `rel="ugc"` in addition to `link_rel`
+    pub fn ugc_domains(&mut self, value: Vec<&'a str>) -> &mut Self {
+        self.ugc_domains = value;
+        self
+    }
+
+    /// Adds or strips query parameters in anchor `href` values
+    pub fn url_rewriter(&mut self, value: UrlRewriter<'a>) -> &mut Self {
+        self.url_rewriter = value;
+        self
+    }
+
+    /// Wraps each heading with an id, and the content up to the next heading of the same or a
+    /// higher level, in nested `<section aria-labelledby="…">` elements
+    pub fn wrap_sections(&mut self, value: bool) -> &mut Self {
+        self.wrap_sections = value;
+        self
+    }
+
+    fn link_class(&self, href: &str) -> LinkClass {
+        let in_any_domain =
+            |domains: &[&str]| domains.iter().any(|domain| url_in_domain(href, domain));
+        let canonical_host = self.canonical_root_url.and_then(url_host);
+        if relative_url(href)
+            || canonical_host.is_some_and(|host| url_in_domain(href, &host))
+            || in_any_domain(&self.internal_domains)
+        {
+            LinkClass::Internal
+        } else if in_any_domain(&self.sponsored_domains) {
+            LinkClass::Sponsored
+        } else if in_any_domain(&self.ugc_domains) {
+            LinkClass::UserGenerated
+        } else {
+            LinkClass::External
+        }
+    }
+
+    /// `true` for links to pages of this site, which are relative or on the canonical host, so
+    /// can be checked against the site manifest.  Subdomains and other internal domains are
+    /// separate sites.
+    fn site_link(&self, href: &str) -> bool {
+        if href.starts_with("//") {
+            return false;
+        }
+        relative_url(href)
+            || self
+                .canonical_root_url
+                .and_then(url_host)
+                .zip(url_host(href))
+                .is_some_and(|(canonical_host, host)| canonical_host.eq_ignore_ascii_case(&host))
+    }
+
     fn process_child(_child: &mut Handle) -> bool {
         true
     }
@@ -105,18 +388,30 @@ impl<'a> Builder<'a> {
                 .into_iter()
                 .rev(),
         );
-        let mut already_matched = false;
+        let mut state = ProcessState::default();
 
         while let Some(mut node) = stack.pop() {
             let parent = node.parent.replace(None).expect("a node in the DOM will have a parent, except the root, which is not processed")
                 .upgrade().expect("a node's parent will be pointed to by its parent (or the root pointer), and will not be dropped");
             let pass_process = Builder::<'a>::process_child(&mut node);
             if pass_process {
-                self.adjust_node_attributes(&mut node, &link_rel, &link_target);
+                let expanded_nodes = self.expanded_nodes(&node, &parent, &mut dom, &mut state);
+                if let Some(value) = expanded_nodes {
+                    // new nodes are processed in turn, so links get the usual attributes
+                    for new_node in value.into_iter().rev() {
+                        new_node.parent.set(Some(Rc::downgrade(&parent)));
+                        stack.push(new_node);
+                    }
+                    removed.push(node);
+                    continue;
+                }
+                self.adjust_node_attributes(&mut node, &link_rel, &link_target, &mut state);
+                Builder::<'a>::collect_image(&node, &mut state.images);
+                Builder::<'a>::collect_id(&node, &mut state.element_ids);
                 Builder::<'a>::adjust_node_children(&mut node, &mut dom);
                 if self.search_term.is_some() {
                     if let Some(value) =
-                        self.replacement_node(&mut node, &mut dom, &mut already_matched)
+                        self.replacement_node(&mut node, &mut dom, &mut state.already_matched)
                     {
                         // node should be a TextNode and so have no children to check so OK to
                         // continue here
@@ -142,7 +437,51 @@ impl<'a> Builder<'a> {
         while let Some(node) = removed.pop() {
             removed.extend_from_slice(&mem::take(&mut *node.children.borrow_mut())[..]);
         }
-        Document(dom)
+        self.apply_typography(&body);
+        if self.wrap_sections {
+            section::wrap_sections(&body, &dom);
+        }
+        let ProcessState {
+            links,
+            images,
+            mut diagnostics,
+            element_ids,
+            fragment_links,
+            obfuscated_emails,
+            ..
+        } = state;
+        for (link_index, fragment) in fragment_links {
+            if !element_ids.contains(&fragment) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::UnknownFragment,
+                        &format!("#{fragment}"),
+                        &format!("Link to missing fragment #{fragment} in this document"),
+                        None,
+                    )
+                    .for_link(link_index),
+                );
+            }
+        }
+        diagnostics.sort_by_key(Diagnostic::link_index);
+        Document {
+            dom,
+            links,
+            images,
+            diagnostics,
+            obfuscated_emails,
+        }
+    }
+
+    /// Adds no-break spaces and soft hyphens.  Applied last, so autolink, glossary and search
+    /// matching see the original text.
+    fn apply_typography(&self, body: &Handle) {
+        if let Some(rules) = self.non_breaking_spaces {
+            rules.apply(body);
+        }
+        if let Some(hyphenation) = self.hyphenation {
+            hyphenation.apply(body);
+        }
     }
 
     pub fn process(&self, src: &str) -> Document {
@@ -157,6 +496,7 @@ impl<'a> Builder<'a> {
         child: &mut Handle,
         link_rel: &Option<StrTendril>,
         link_target: &Option<StrTendril>,
+        state: &mut ProcessState,
     ) {
         if let NodeData::Element {
             ref name,
@@ -166,23 +506,70 @@ impl<'a> Builder<'a> {
         {
             if &*name.local == "a" {
                 let mut attrs = attrs.borrow_mut();
+                let generated_anchor = attrs
+                    .iter()
+                    .any(|attr| &*attr.name.local == "class" && &*attr.value == "heading-anchor");
                 if let Some(attr) = attrs.iter_mut().find(|attr| &*attr.name.local == "href") {
+                    let source_href = attr.value.to_string();
+                    let link_class = self.link_class(&attr.value);
+                    if self.obfuscate_email {
+                        if let Some(address) = source_href.strip_prefix("mailto:") {
+                            let address =
+                                address.split_once('?').map_or(address, |(value, _)| value);
+                            state.obfuscated_emails.push(address.to_string());
+                        }
+                    }
+                    let link_index = state.links.len();
+                    if !generated_anchor && self.site_link(&attr.value) {
+                        self.check_site_link(attr, &source_href, link_index, state);
+                    }
                     if relative_url(&attr.value) {
                         if let Some(root_url_value) = self.canonical_root_url {
                             let pathname = &*attr.value;
                             attr.value = format!("{root_url_value}{pathname}").into();
                         }
-                    } else {
+                    }
+                    if let Some(rewritten_url) = self.url_rewriter.rewrite(&attr.value) {
+                        attr.value = rewritten_url.into();
+                    }
+                    if !generated_anchor {
+                        let kind = if source_href.starts_with('#') {
+                            LinkKind::Fragment
+                        } else if link_class == LinkClass::Internal {
+                            LinkKind::Internal
+                        } else {
+                            LinkKind::External
+                        };
+                        state.links.push(Link {
+                            href: attr.value.to_string(),
+                            text: text_content(child),
+                            kind,
+                            line: None,
+                            source_href,
+                        });
+                    }
+                    if link_class != LinkClass::Internal {
                         if let Some(ref link_target) = *link_target {
                             attrs.push(Attribute {
                                 name: QualName::new(None, ns!(), local_name!("target")),
                                 value: link_target.clone(),
                             });
                         }
-                        if let Some(ref link_rel) = *link_rel {
+                        let link_rel = match (link_class, link_rel) {
+                            (LinkClass::Sponsored, Some(value)) => {
+                                Some(format_tendril!("sponsored {value}"))
+                            }
+                            (LinkClass::Sponsored, None) => Some(format_tendril!("sponsored")),
+                            (LinkClass::UserGenerated, Some(value)) => {
+                                Some(format_tendril!("ugc {value}"))
+                            }
+                            (LinkClass::UserGenerated, None) => Some(format_tendril!("ugc")),
+                            (_, value) => value.clone(),
+                        };
+                        if let Some(link_rel) = link_rel {
                             attrs.push(Attribute {
                                 name: QualName::new(None, ns!(), local_name!("rel")),
-                                value: link_rel.clone(),
+                                value: link_rel,
                             });
                         }
                     }
@@ -193,6 +580,294 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// Rewrites `attr`, an internal link `href`, through the site manifest redirects and checks
+    /// the result resolves, recording diagnostics against the link at `link_index`
+    fn check_site_link(
+        &self,
+        attr: &mut Attribute,
+        source_href: &str,
+        link_index: usize,
+        state: &mut ProcessState,
+    ) {
+        let Some(manifest) = &self.site_manifest else {
+            return;
+        };
+        if let Some(redirected_href) = manifest.redirect(&attr.value) {
+            state.diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticKind::Redirected,
+                    source_href,
+                    &format!("Link redirected from {source_href} to {redirected_href}"),
+                    None,
+                )
+                .for_link(link_index),
+            );
+            attr.value = redirected_href.into();
+        }
+        if let Some(fragment) = attr.value.strip_prefix('#') {
+            state
+                .fragment_links
+                .push((link_index, fragment.to_string()));
+        } else if let Some(diagnostic) = manifest.check(&attr.value) {
+            state.diagnostics.push(diagnostic.for_link(link_index));
+        }
+    }
+
+    /*
+     * Replacement nodes for a text node split by autolinking, glossary matching or emoji
+     * labelling, in that order.  Returns `None` if the node is unchanged.
+     */
+    fn expanded_nodes(
+        &self,
+        node: &Handle,
+        parent: &Handle,
+        dom: &mut RcDom,
+        state: &mut ProcessState,
+    ) -> Option<Vec<Handle>> {
+        if self.autolink {
+            if let Some(value) = Builder::<'a>::autolinked_nodes(node, parent, dom) {
+                return Some(value);
+            }
+        }
+        if let Some(value) = self.glossary_nodes(node, parent, dom, &mut state.used_glossary_terms)
+        {
+            return Some(value);
+        }
+        if self.accessible_emoji {
+            return Builder::<'a>::emoji_nodes(node, parent, dom);
+        }
+        None
+    }
+
+    /*
+     * Splits a text node, outside links and code, into text and anchor nodes for any bare URLs,
+     * `www.` hosts or email addresses it contains.  Returns `None` if there are none.
+     */
+    fn autolinked_nodes(child: &Handle, parent: &Handle, dom: &mut RcDom) -> Option<Vec<Handle>> {
+        let NodeData::Text { ref contents } = child.data else {
+            return None;
+        };
+        if within_element(parent, &["a", "code", "pre", "script", "style"]) {
+            return None;
+        }
+        let text = contents.borrow();
+        let autolinks = find_autolinks(&text);
+        if autolinks.is_empty() {
+            return None;
+        }
+        let mut result = Vec::new();
+        let mut index = 0;
+        for autolink in autolinks {
+            if autolink.start > index {
+                result.push(Node::new(NodeData::Text {
+                    contents: RefCell::new(text[index..autolink.start].into()),
+                }));
+            }
+            let anchor = Node::new(NodeData::Element {
+                name: QualName::new(None, ns!(), local_name!("a")),
+                attrs: RefCell::new(vec![Attribute {
+                    name: QualName::new(None, ns!(), local_name!("href")),
+                    value: autolink.href.into(),
+                }]),
+                template_contents: RefCell::new(None),
+                mathml_annotation_xml_integration_point: false,
+            });
+            dom.append(
+                &anchor,
+                AppendNode(Node::new(NodeData::Text {
+                    contents: RefCell::new(text[autolink.start..autolink.end].into()),
+                })),
+            );
+            result.push(anchor);
+            index = autolink.end;
+        }
+        if index < text.len() {
+            result.push(Node::new(NodeData::Text {
+                contents: RefCell::new(text[index..].into()),
+            }));
+        }
+        Some(result)
+    }
+
+    /*
+     * Splits a body text node into text and `<abbr>` or anchor nodes for glossary terms and
+     * abbreviations.  Returns `None` if there are no matches.
+     */
+    fn glossary_nodes(
+        &self,
+        child: &Handle,
+        parent: &Handle,
+        dom: &mut RcDom,
+        used_terms: &mut HashSet<usize>,
+    ) -> Option<Vec<Handle>> {
+        let NodeData::Text { ref contents } = child.data else {
+            return None;
+        };
+        if within_element(
+            parent,
+            &[
+                "a", "abbr", "code", "pre", "h1", "h2", "h3", "h4", "h5", "h6", "script", "style",
+            ],
+        ) {
+            return None;
+        }
+        let text = contents.borrow();
+        let matches = self.glossary.find_matches(&text, used_terms);
+        if matches.is_empty() {
+            return None;
+        }
+        let mut result = Vec::new();
+        let mut index = 0;
+        for glossary_match in matches {
+            if glossary_match.start > index {
+                result.push(Node::new(NodeData::Text {
+                    contents: RefCell::new(text[index..glossary_match.start].into()),
+                }));
+            }
+            let (name, attrs) = match glossary_match.element {
+                GlossaryElement::Abbreviation { title } => (
+                    local_name!("abbr"),
+                    vec![Attribute {
+                        name: QualName::new(None, ns!(), local_name!("title")),
+                        value: title.into(),
+                    }],
+                ),
+                GlossaryElement::Link { href, title } => {
+                    let mut attrs = vec![Attribute {
+                        name: QualName::new(None, ns!(), local_name!("href")),
+                        value: href.into(),
+                    }];
+                    if let Some(value) = title {
+                        attrs.push(Attribute {
+                            name: QualName::new(None, ns!(), local_name!("title")),
+                            value: value.into(),
+                        });
+                    }
+                    (local_name!("a"), attrs)
+                }
+            };
+            let element = Node::new(NodeData::Element {
+                name: QualName::new(None, ns!(), name),
+                attrs: RefCell::new(attrs),
+                template_contents: RefCell::new(None),
+                mathml_annotation_xml_integration_point: false,
+            });
+            dom.append(
+                &element,
+                AppendNode(Node::new(NodeData::Text {
+                    contents: RefCell::new(text[glossary_match.start..glossary_match.end].into()),
+                })),
+            );
+            result.push(element);
+            index = glossary_match.end;
+        }
+        if index < text.len() {
+            result.push(Node::new(NodeData::Text {
+                contents: RefCell::new(text[index..].into()),
+            }));
+        }
+        Some(result)
+    }
+
+    /*
+     * Splits a text node, outside code, into text and `<span role="img">` nodes for any emoji it
+     * contains.  Returns `None` if there are none, or the text is already labelled.
+     */
+    fn emoji_nodes(child: &Handle, parent: &Handle, dom: &mut RcDom) -> Option<Vec<Handle>> {
+        let NodeData::Text { ref contents } = child.data else {
+            return None;
+        };
+        if within_element(parent, &["code", "pre", "script", "style"]) {
+            return None;
+        }
+        if let NodeData::Element { ref attrs, .. } = parent.data {
+            if attrs
+                .borrow()
+                .iter()
+                .any(|attr| &*attr.name.local == "role" && &*attr.value == "img")
+            {
+                return None;
+            }
+        }
+        let text = contents.borrow();
+        let matches = find_emoji(&text);
+        if matches.is_empty() {
+            return None;
+        }
+        let mut result = Vec::new();
+        let mut index = 0;
+        for emoji_match in matches {
+            if emoji_match.start > index {
+                result.push(Node::new(NodeData::Text {
+                    contents: RefCell::new(text[index..emoji_match.start].into()),
+                }));
+            }
+            let span = Node::new(NodeData::Element {
+                name: QualName::new(None, ns!(), local_name!("span")),
+                attrs: RefCell::new(vec![
+                    Attribute {
+                        name: QualName::new(None, ns!(), local_name!("role")),
+                        value: "img".into(),
+                    },
+                    Attribute {
+                        name: QualName::new(None, ns!(), local_name!("aria-label")),
+                        value: emoji_match.emoji.name().into(),
+                    },
+                ]),
+                template_contents: RefCell::new(None),
+                mathml_annotation_xml_integration_point: false,
+            });
+            dom.append(
+                &span,
+                AppendNode(Node::new(NodeData::Text {
+                    contents: RefCell::new(text[emoji_match.start..emoji_match.end].into()),
+                })),
+            );
+            result.push(span);
+            index = emoji_match.end;
+        }
+        if index < text.len() {
+            result.push(Node::new(NodeData::Text {
+                contents: RefCell::new(text[index..].into()),
+            }));
+        }
+        Some(result)
+    }
+
+    fn collect_id(child: &Handle, element_ids: &mut HashSet<String>) {
+        if let NodeData::Element { ref attrs, .. } = child.data {
+            if let Some(attr) = attrs.borrow().iter().find(|attr| &*attr.name.local == "id") {
+                element_ids.insert(attr.value.to_string());
+            }
+        }
+    }
+
+    fn collect_image(child: &Handle, images: &mut Vec<Image>) {
+        if let NodeData::Element {
+            ref name,
+            ref attrs,
+            ..
+        } = child.data
+        {
+            if &*name.local == "img" {
+                let attrs = attrs.borrow();
+                let attribute = |attribute_name: &str| {
+                    attrs
+                        .iter()
+                        .find(|attr| &*attr.name.local == attribute_name)
+                        .map(|attr| attr.value.to_string())
+                };
+                if let Some(src) = attribute("src") {
+                    images.push(Image {
+                        src,
+                        alt: attribute("alt"),
+                        title: attribute("title"),
+                    });
+                }
+            }
+        }
+    }
+
     /*
      * Searches text content within `child` for the search term. Returns `None` if no match is
      * found and returns `Some(replacement)` if a match is found. `replacement` will have occurrences
@@ -317,11 +992,48 @@ impl<'a> Builder<'a> {
     }
 }
 
-pub struct Document(RcDom);
+pub struct Document {
+    dom: RcDom,
+    links: Vec<Link>,
+    images: Vec<Image>,
+    diagnostics: Vec<Diagnostic>,
+    obfuscated_emails: Vec<String>,
+}
 
 impl Document {
-    fn serialize_opts() -> SerializeOpts {
-        SerializeOpts::default()
+    /// Anchors in document order, excluding generated heading anchors
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn images(&self) -> &[Image] {
+        &self.images
+    }
+
+    /// Link problems and rewrites found checking against the site manifest
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Fills in link source lines from `(href, line)` pairs, listed in source order.  Links are
+    /// matched in order on their `href` before any rewriting, so anchors missing from
+    /// `source_links`, such as ones added by raw HTML, keep a `None` line.
+    pub fn set_link_source_lines(&mut self, source_links: &[(String, usize)]) {
+        let mut remaining = source_links.iter();
+        for link in &mut self.links {
+            let mut lookahead = remaining.clone();
+            if let Some((_, line)) = lookahead.find(|(href, _)| *href == link.source_href) {
+                link.line = Some(*line);
+                remaining = lookahead;
+            }
+        }
+        for diagnostic in &mut self.diagnostics {
+            let line = self
+                .links
+                .get(diagnostic.link_index())
+                .and_then(|link| link.line);
+            diagnostic.set_line(line);
+        }
     }
 }
 
@@ -329,16 +1041,23 @@ impl Clone for Document {
     fn clone(&self) -> Self {
         let parser = Builder::make_parser();
         let dom = parser.one(&self.to_string()[..]);
-        Document(dom)
+        Document {
+            dom,
+            links: self.links.clone(),
+            images: self.images.clone(),
+            diagnostics: self.diagnostics.clone(),
+            obfuscated_emails: self.obfuscated_emails.clone(),
+        }
     }
 }
 
 impl Display for Document {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let opts = Self::serialize_opts();
         let mut ret_val = Vec::new();
-        let inner: SerializableHandle = self.0.document.children.borrow()[0].clone().into();
-        serialize(&mut ret_val, &inner, opts)
+        let inner: SerializableHandle = self.dom.document.children.borrow()[0].clone().into();
+        let mut serializer = DocumentSerializer::new(&mut ret_val, &self.obfuscated_emails);
+        inner
+            .serialize(&mut serializer, TraversalScope::ChildrenOnly(None))
             .expect("Writing to a string shouldn't fail (expect on OOM)");
         String::from_utf8(ret_val)
             .expect("html5ever only supports UTF8")
@@ -346,15 +1065,15 @@ impl Display for Document {
     }
 }
 
-pub fn process_html(
-    html: &str,
-    canonical_root_url: Option<&str>,
-    search_term: Option<&str>,
-) -> String {
-    Builder::new()
+/// Builder with the link attributes used for HTML generated from Markdown
+pub fn markdown_html_builder<'a>(
+    canonical_root_url: Option<&'a str>,
+    search_term: Option<&'a str>,
+) -> Builder<'a> {
+    let mut builder = Builder::new();
+    builder
         .link_rel(Some("nofollow noopener noreferrer"))
         .canonical_root_url(canonical_root_url)
-        .search_term(search_term)
-        .process(html)
-        .to_string()
+        .search_term(search_term);
+    builder
 }
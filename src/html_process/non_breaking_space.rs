@@ -0,0 +1,174 @@
+use super::dom::{Handle, NodeData};
+use crate::text::Locale;
+
+const NO_BREAK_SPACE: char = '\u{a0}';
+
+/// Elements whose text is left unchanged, along with their descendants
+const SKIPPED_ELEMENTS: [&str; 5] = ["code", "kbd", "pre", "script", "style"];
+
+/// Elements whose last two words are joined
+const WIDOW_ELEMENTS: [&str; 7] = ["p", "h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Units kept on the same line as a preceding number, as in `10 km`
+const UNITS: [&str; 38] = [
+    "%", "\u{2030}", "\u{b0}", "\u{b0}C", "\u{b0}F", "K", "nm", "\u{b5}m", "mm", "cm", "m", "km",
+    "mi", "ft", "mg", "g", "kg", "t", "ml", "l", "L", "ms", "s", "min", "h", "Hz", "kHz", "MHz",
+    "GHz", "W", "kW", "kWh", "kB", "MB", "GB", "TB", "px", "\u{20ac}",
+];
+
+/// Rules for replacing spaces with no-break spaces, so short words and units do not end up alone
+/// at the start or end of a line
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NonBreakingSpaceRules {
+    number_units: bool,
+    single_letter_words: bool,
+    widows: bool,
+}
+
+impl NonBreakingSpaceRules {
+    /// Joins widows and number units for every locale, and single-letter words for Czech and
+    /// Polish
+    pub fn for_locale(locale: Locale) -> Self {
+        NonBreakingSpaceRules {
+            number_units: true,
+            single_letter_words: locale.joins_single_letter_words(),
+            widows: true,
+        }
+    }
+
+    /// Joins a number and a following unit, as in `10 km`
+    pub fn number_units(&mut self, value: bool) -> &mut Self {
+        self.number_units = value;
+        self
+    }
+
+    /// Joins single-letter words, like the Czech `v` or Polish `w`, to the following word
+    pub fn single_letter_words(&mut self, value: bool) -> &mut Self {
+        self.single_letter_words = value;
+        self
+    }
+
+    /// Joins the last two words of paragraphs and headings
+    pub fn widows(&mut self, value: bool) -> &mut Self {
+        self.widows = value;
+        self
+    }
+
+    /// Applies the rules to text within `node`, skipping code and preformatted text
+    pub(super) fn apply(self, node: &Handle) {
+        let mut stack = vec![node.clone()];
+        while let Some(current) = stack.pop() {
+            match current.data {
+                NodeData::Text { ref contents }
+                    if self.number_units || self.single_letter_words =>
+                {
+                    let updated = self.join_words(&contents.borrow());
+                    if let Some(value) = updated {
+                        *contents.borrow_mut() = value.into();
+                    }
+                }
+                NodeData::Element { ref name, .. } => {
+                    if SKIPPED_ELEMENTS.contains(&&*name.local) {
+                        continue;
+                    }
+                    if self.widows && WIDOW_ELEMENTS.contains(&&*name.local) {
+                        join_last_words(&current);
+                    }
+                }
+                _ => {}
+            }
+            stack.extend(current.children.borrow().iter().rev().cloned());
+        }
+    }
+
+    /// Text with spaces after single-letter words and between numbers and units replaced, or
+    /// `None` if nothing changed
+    fn join_words(self, text: &str) -> Option<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::with_capacity(text.len());
+        let mut changed = false;
+        for (index, c) in chars.iter().enumerate() {
+            if *c == ' ' && index > 0 {
+                let previous = chars[index - 1];
+                let single_letter = self.single_letter_words
+                    && previous.is_alphabetic()
+                    && index
+                        .checked_sub(2)
+                        .is_none_or(|position| word_boundary(chars[position]));
+                let number_unit = self.number_units
+                    && previous.is_ascii_digit()
+                    && starts_with_unit(&chars[index + 1..]);
+                if single_letter || number_unit {
+                    result.push(NO_BREAK_SPACE);
+                    changed = true;
+                    continue;
+                }
+            }
+            result.push(*c);
+        }
+        changed.then_some(result)
+    }
+}
+
+/// `true` for characters which may precede a word, including opening quotes and brackets
+fn word_boundary(c: char) -> bool {
+    c.is_whitespace() || "([{\"'\u{201e}\u{201a}\u{201c}\u{2018}\u{ab}\u{bb}".contains(c)
+}
+
+/// `true` if `chars` start with a unit, followed by the end of the text or a non-alphanumeric
+/// character
+fn starts_with_unit(chars: &[char]) -> bool {
+    let word_end = chars
+        .iter()
+        .position(|c| !(c.is_alphanumeric() || "%\u{2030}\u{b0}\u{b5}\u{20ac}".contains(*c)))
+        .unwrap_or(chars.len());
+    let word: String = chars[..word_end].iter().collect();
+    UNITS.contains(&word.as_str())
+}
+
+/// Replaces the space before the last word of `node` text with a no-break space.  Text within code
+/// and generated heading anchors is ignored.
+fn join_last_words(node: &Handle) {
+    let mut text_nodes = Vec::new();
+    let mut stack = vec![node.clone()];
+    while let Some(current) = stack.pop() {
+        match current.data {
+            NodeData::Text { .. } => text_nodes.push(current.clone()),
+            NodeData::Element {
+                ref name,
+                ref attrs,
+                ..
+            } => {
+                let heading_anchor = attrs
+                    .borrow()
+                    .iter()
+                    .any(|attr| &*attr.name.local == "class" && &*attr.value == "heading-anchor");
+                if heading_anchor || SKIPPED_ELEMENTS.contains(&&*name.local) {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        stack.extend(current.children.borrow().iter().rev().cloned());
+    }
+
+    let mut seen_word = false;
+    for text_node in text_nodes.iter().rev() {
+        if let NodeData::Text { ref contents } = text_node.data {
+            let text = contents.borrow().to_string();
+            for (index, c) in text.char_indices().rev() {
+                if c == ' ' && seen_word {
+                    let mut updated = String::with_capacity(text.len() + 1);
+                    updated.push_str(&text[..index]);
+                    updated.push(NO_BREAK_SPACE);
+                    updated.push_str(&text[index + 1..]);
+                    *contents.borrow_mut() = updated.into();
+                    return;
+                }
+                if c.is_alphanumeric() {
+                    seen_word = true;
+                }
+            }
+        }
+    }
+}
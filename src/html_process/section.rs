@@ -0,0 +1,68 @@
+use std::{cell::RefCell, mem};
+
+use html5ever::{
+    Attribute, QualName,
+    interface::tree_builder::{AppendNode, TreeSink},
+    local_name, ns,
+};
+
+use super::dom::{Handle, Node, NodeData, RcDom};
+
+/// Level and id of a heading element with an `id` attribute
+fn heading(node: &Handle) -> Option<(u8, String)> {
+    let NodeData::Element {
+        ref name,
+        ref attrs,
+        ..
+    } = node.data
+    else {
+        return None;
+    };
+    let level = match &*name.local {
+        "h1" => 1,
+        "h2" => 2,
+        "h3" => 3,
+        "h4" => 4,
+        "h5" => 5,
+        "h6" => 6,
+        _ => return None,
+    };
+    attrs
+        .borrow()
+        .iter()
+        .find(|attr| &*attr.name.local == "id")
+        .map(|attr| (level, attr.value.to_string()))
+}
+
+/// Wraps each top-level heading with an id, and the content following it up to the next heading of
+/// the same or a higher level, in a `<section aria-labelledby="…">`.  Sections for lower level
+/// headings are nested.
+pub(super) fn wrap_sections(body: &Handle, dom: &RcDom) {
+    let children = mem::take(&mut *body.children.borrow_mut());
+    let mut sections: Vec<(u8, Handle)> = Vec::new();
+    for child in children {
+        child.parent.set(None);
+        if let Some((level, id)) = heading(&child) {
+            while sections
+                .last()
+                .is_some_and(|(section_level, _)| *section_level >= level)
+            {
+                sections.pop();
+            }
+            let section = Node::new(NodeData::Element {
+                name: QualName::new(None, ns!(), local_name!("section")),
+                attrs: RefCell::new(vec![Attribute {
+                    name: QualName::new(None, ns!(), local_name!("aria-labelledby")),
+                    value: id.into(),
+                }]),
+                template_contents: RefCell::new(None),
+                mathml_annotation_xml_integration_point: false,
+            });
+            let parent = sections.last().map_or(body, |(_, section)| section);
+            dom.append(parent, AppendNode(section.clone()));
+            sections.push((level, section));
+        }
+        let parent = sections.last().map_or(body, |(_, section)| section);
+        dom.append(parent, AppendNode(child));
+    }
+}
@@ -0,0 +1,230 @@
+/* Copyright 2014-2017 The html5ever Project Developers. See the
+ * COPYRIGHT file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::io::{self, Write};
+
+use html5ever::{
+    LocalName, QualName, local_name, ns,
+    serialize::{AttrRef, Serializer},
+};
+
+use super::{autolink::entity_encode, hyphenation::SOFT_HYPHEN};
+
+/// Elements without end tags, whose children are not serialised
+const VOID_ELEMENTS: [&str; 18] = [
+    "area", "base", "basefont", "bgsound", "br", "col", "embed", "frame", "hr", "img", "input",
+    "keygen", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Elements whose text is written without escaping
+const RAW_TEXT_ELEMENTS: [&str; 8] = [
+    "style",
+    "script",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "plaintext",
+    "noscript",
+];
+
+/// Elements whose text is serialised without soft hyphen or email address entities
+const VERBATIM_ELEMENTS: [&str; 2] = ["code", "pre"];
+
+/// Characters which may appear in an email address, next to a matched address
+fn email_character(c: char) -> bool {
+    c.is_alphanumeric() || "-_+@%".contains(c)
+}
+
+struct ElementInfo {
+    name: Option<LocalName>,
+    ignore_children: bool,
+}
+
+/// HTML serialiser, following html5ever's `HtmlSerializer`, which adds `&shy;` entities for soft
+/// hyphens and entity encodes `obfuscated_emails`, in text and `mailto:` link `href` attributes.
+/// Text within code and preformatted elements is left unchanged.
+pub(super) struct DocumentSerializer<'a, W: Write> {
+    writer: W,
+    obfuscated_emails: &'a [String],
+    stack: Vec<ElementInfo>,
+    verbatim_depth: usize,
+}
+
+impl<'a, W: Write> DocumentSerializer<'a, W> {
+    pub(super) fn new(writer: W, obfuscated_emails: &'a [String]) -> Self {
+        DocumentSerializer {
+            writer,
+            obfuscated_emails,
+            stack: vec![ElementInfo {
+                name: None,
+                ignore_children: false,
+            }],
+            verbatim_depth: 0,
+        }
+    }
+
+    fn parent(&self) -> &ElementInfo {
+        self.stack.last().expect("no parent ElementInfo")
+    }
+
+    fn write_escaped(&mut self, text: &str, attr_mode: bool) -> io::Result<()> {
+        let entities = !attr_mode && self.verbatim_depth == 0;
+        for c in text.chars() {
+            match c {
+                '&' => self.writer.write_all(b"&amp;"),
+                '\u{a0}' => self.writer.write_all(b"&nbsp;"),
+                '"' if attr_mode => self.writer.write_all(b"&quot;"),
+                '<' => self.writer.write_all(b"&lt;"),
+                '>' => self.writer.write_all(b"&gt;"),
+                SOFT_HYPHEN if entities => self.writer.write_all(b"&shy;"),
+                c => write!(self.writer, "{c}"),
+            }?;
+        }
+        Ok(())
+    }
+
+    /// Whether `href` is a `mailto:` link to one of the obfuscated addresses
+    fn obfuscated_mailto(&self, href: &str) -> bool {
+        href.strip_prefix("mailto:")
+            .map(|value| value.split_once('?').map_or(value, |(address, _)| address))
+            .is_some_and(|address| self.obfuscated_emails.iter().any(|value| value == address))
+    }
+
+    /// Byte offset and length of the first obfuscated address in `text`, which is not part of a
+    /// longer address
+    fn next_email(&self, text: &str) -> Option<(usize, usize)> {
+        self.obfuscated_emails
+            .iter()
+            .filter(|address| !address.is_empty())
+            .filter_map(|address| {
+                text.match_indices(address.as_str())
+                    .find(|(start, _)| {
+                        let end = start + address.len();
+                        let before = text[..*start].chars().next_back();
+                        let mut after = text[end..].chars();
+                        let bounded_end = match after.next() {
+                            None => true,
+                            Some('.') => !after.next().is_some_and(email_character),
+                            Some(value) => !email_character(value),
+                        };
+                        !before.is_some_and(|value| email_character(value) || value == '.')
+                            && bounded_end
+                    })
+                    .map(|(start, _)| (start, address.len()))
+            })
+            .min_by_key(|(start, _)| *start)
+    }
+
+    fn write_text_entities(&mut self, text: &str) -> io::Result<()> {
+        let mut rest = text;
+        while let Some((start, length)) = self.next_email(rest) {
+            self.write_escaped(&rest[..start], false)?;
+            self.writer
+                .write_all(entity_encode(&rest[start..start + length]).as_bytes())?;
+            rest = &rest[start + length..];
+        }
+        self.write_escaped(rest, false)
+    }
+}
+
+impl<W: Write> Serializer for DocumentSerializer<'_, W> {
+    fn start_elem<'b, AttrIter>(&mut self, name: QualName, attrs: AttrIter) -> io::Result<()>
+    where
+        AttrIter: Iterator<Item = AttrRef<'b>>,
+    {
+        let html_name = (name.ns == ns!(html)).then(|| name.local.clone());
+        if self.parent().ignore_children {
+            self.stack.push(ElementInfo {
+                name: html_name,
+                ignore_children: true,
+            });
+            return Ok(());
+        }
+
+        write!(self.writer, "<{}", name.local)?;
+        for (attr_name, value) in attrs {
+            self.writer.write_all(b" ")?;
+            match attr_name.ns {
+                ns!(xml) => self.writer.write_all(b"xml:")?,
+                ns!(xmlns) if attr_name.local != local_name!("xmlns") => {
+                    self.writer.write_all(b"xmlns:")?;
+                }
+                ns!(xlink) => self.writer.write_all(b"xlink:")?,
+                _ => (),
+            }
+            write!(self.writer, "{}=\"", attr_name.local)?;
+            if attr_name.local == local_name!("href") && self.obfuscated_mailto(value) {
+                self.writer.write_all(entity_encode(value).as_bytes())?;
+            } else {
+                self.write_escaped(value, true)?;
+            }
+            self.writer.write_all(b"\"")?;
+        }
+        self.writer.write_all(b">")?;
+
+        let ignore_children = html_name
+            .as_ref()
+            .is_some_and(|value| VOID_ELEMENTS.contains(&&**value));
+        if html_name
+            .as_ref()
+            .is_some_and(|value| VERBATIM_ELEMENTS.contains(&&**value))
+        {
+            self.verbatim_depth += 1;
+        }
+        self.stack.push(ElementInfo {
+            name: html_name,
+            ignore_children,
+        });
+        Ok(())
+    }
+
+    fn end_elem(&mut self, name: QualName) -> io::Result<()> {
+        let info = self.stack.pop().expect("no ElementInfo");
+        if info.ignore_children {
+            return Ok(());
+        }
+        if info
+            .name
+            .as_ref()
+            .is_some_and(|value| VERBATIM_ELEMENTS.contains(&&**value))
+        {
+            self.verbatim_depth -= 1;
+        }
+        write!(self.writer, "</{}>", name.local)
+    }
+
+    fn write_text(&mut self, text: &str) -> io::Result<()> {
+        let raw = self
+            .parent()
+            .name
+            .as_ref()
+            .is_some_and(|value| RAW_TEXT_ELEMENTS.contains(&&**value));
+        if raw {
+            self.writer.write_all(text.as_bytes())
+        } else if self.verbatim_depth > 0 || self.obfuscated_emails.is_empty() {
+            self.write_escaped(text, false)
+        } else {
+            self.write_text_entities(text)
+        }
+    }
+
+    fn write_comment(&mut self, text: &str) -> io::Result<()> {
+        write!(self.writer, "<!--{text}-->")
+    }
+
+    fn write_doctype(&mut self, name: &str) -> io::Result<()> {
+        write!(self.writer, "<!DOCTYPE {name}>")
+    }
+
+    fn write_processing_instruction(&mut self, target: &str, data: &str) -> io::Result<()> {
+        write!(self.writer, "<?{target} {data}>")
+    }
+}
@@ -1,4 +1,20 @@
-use crate::html_process::{Builder, process_html, relative_url};
+use std::collections::HashMap;
+
+use crate::{
+    html_process::{
+        Builder, Diagnostic, DiagnosticKind, Glossary, GlossaryTerm, Hyphenation,
+        NonBreakingSpaceRules, SiteManifest, markdown_html_builder, relative_url,
+    },
+    text::Locale,
+    url_utility::{QueryParameterRule, UrlRewriter},
+};
+
+/// HTML processed with the link attributes used for HTML generated from Markdown
+fn process_html(html: &str, canonical_root_url: Option<&str>, search_term: Option<&str>) -> String {
+    markdown_html_builder(canonical_root_url, search_term)
+        .process(html)
+        .to_string()
+}
 
 #[test]
 fn test_builder_process() {
@@ -95,3 +111,366 @@ fn search_html_highlight_does_nothing_when_there_are_no_matches() {
         "<h2>Heading</h2><p>Nobody likes maple in their apple flavoured Snapple. APPLE</p>";
     assert_eq!(result, expected);
 }
+
+#[test]
+fn builder_treats_internal_domain_links_like_relative_links() {
+    let result = Builder::new()
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .internal_domains(vec!["example.com"])
+        .process(r#"<a href="https://blog.example.com/post">Post</a><a href="https://example.org">Other</a>"#)
+        .to_string();
+    let expected = r#"<a href="https://blog.example.com/post">Post</a><a href="https://example.org" target="_blank" rel="nofollow noopener noreferrer">Other</a>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_treats_canonical_root_url_domain_as_internal() {
+    let result = process_html(
+        r#"<a href="https://www.example.com/about">About</a>"#,
+        Some("https://example.com"),
+        None,
+    );
+    let expected = r#"<a href="https://www.example.com/about">About</a>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_adds_sponsored_and_ugc_rel_values() {
+    let result = Builder::new()
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .sponsored_domains(vec!["shop.example"])
+        .ugc_domains(vec!["forum.example"])
+        .process(r#"<a href="https://shop.example/item">Item</a><a href="https://forum.example/t/1">Thread</a>"#)
+        .to_string();
+    let expected = r#"<a href="https://shop.example/item" target="_blank" rel="sponsored nofollow noopener noreferrer">Item</a><a href="https://forum.example/t/1" target="_blank" rel="ugc nofollow noopener noreferrer">Thread</a>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_omits_target_when_link_target_disabled() {
+    let result = Builder::new()
+        .link_target(None)
+        .process(r#"<a href="https://example.com">Example</a>"#)
+        .to_string();
+    let expected = r#"<a href="https://example.com" rel="noopener noreferrer">Example</a>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_rewrites_link_query_parameters() {
+    let mut url_rewriter = UrlRewriter::default();
+    url_rewriter
+        .append_parameters(vec![QueryParameterRule::new(
+            "example.com",
+            vec![("utm_source", "newsletter")],
+        )])
+        .strip_parameters(vec!["utm_*", "fbclid"]);
+    let result = Builder::new()
+        .url_rewriter(url_rewriter)
+        .process(r#"<a href="https://example.com/post?id=1&amp;utm_source=twitter">Post</a><a href="https://example.org/?fbclid=abc&amp;utm_medium=social">Other</a><a href="/about">About</a>"#)
+        .to_string();
+    let expected = r#"<a href="https://example.com/post?id=1&amp;utm_source=newsletter" target="_blank" rel="noopener noreferrer">Post</a><a href="https://example.org/" target="_blank" rel="noopener noreferrer">Other</a><a href="/about">About</a>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_keeps_encoding_of_unchanged_query_parameters() {
+    let mut url_rewriter = UrlRewriter::default();
+    url_rewriter.strip_parameters(vec!["utm_*"]);
+    let result = Builder::new()
+        .url_rewriter(url_rewriter)
+        .process(r#"<a href="https://example.org/search?q=a%20b&amp;tag=c%2Bd&amp;utm_source=x">Search</a>"#)
+        .to_string();
+    let expected = r#"<a href="https://example.org/search?q=a%20b&amp;tag=c%2Bd" target="_blank" rel="noopener noreferrer">Search</a>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_reports_links_missing_from_site_manifest() {
+    let mut manifest = SiteManifest::default();
+    manifest.pages(HashMap::from([
+        ("/blog/new-slug", Some(vec!["introduction"])),
+        ("/about", None),
+    ]));
+    let document = Builder::new()
+        .site_manifest(Some(manifest))
+        .process(r##"<h2 id="summary">Summary</h2><a href="/blog/missing">Missing</a><a href="/about/#team">Team</a><a href="/blog/new-slug#conclusion">Conclusion</a><a href="#summary">Summary</a><a href="#details">Details</a>"##);
+    assert_eq!(
+        document.diagnostics(),
+        [
+            Diagnostic::new(
+                DiagnosticKind::UnknownPage,
+                "/blog/missing",
+                "Link to unknown page /blog/missing",
+                None
+            ),
+            Diagnostic::new(
+                DiagnosticKind::UnknownFragment,
+                "/blog/new-slug#conclusion",
+                "Link to missing fragment #conclusion on /blog/new-slug",
+                None
+            ),
+            Diagnostic::new(
+                DiagnosticKind::UnknownFragment,
+                "#details",
+                "Link to missing fragment #details in this document",
+                None
+            ),
+        ]
+    );
+}
+
+#[test]
+fn builder_only_checks_canonical_host_links_against_site_manifest() {
+    let mut manifest = SiteManifest::default();
+    manifest.pages(HashMap::from([("/about", None)]));
+    let document = Builder::new()
+        .canonical_root_url(Some("https://example.com"))
+        .internal_domains(vec!["example.net"])
+        .site_manifest(Some(manifest))
+        .process(r#"<a href="https://docs.example.com/start">Docs</a><a href="https://example.net/shop">Shop</a><a href="https://example.com/missing">Missing</a><a href="/about">About</a>"#);
+    assert_eq!(
+        document.diagnostics(),
+        [Diagnostic::new(
+            DiagnosticKind::UnknownPage,
+            "https://example.com/missing",
+            "Link to unknown page /missing",
+            None
+        )]
+    );
+}
+
+#[test]
+fn builder_checks_document_relative_links_against_page_path() {
+    let mut manifest = SiteManifest::default();
+    manifest.pages(HashMap::from([("/blog/new-slug", None), ("/about", None)]));
+    let html =
+        r#"<a href="old-slug">Old</a><a href="../about">About</a><a href="new-slug#top">New</a>"#;
+    let document = Builder::new().site_manifest(Some(manifest)).process(html);
+    assert_eq!(
+        document.diagnostics(),
+        [
+            Diagnostic::new(
+                DiagnosticKind::Unchecked,
+                "old-slug",
+                "Relative link old-slug not checked, with no page path set",
+                None
+            ),
+            Diagnostic::new(
+                DiagnosticKind::Unchecked,
+                "../about",
+                "Relative link ../about not checked, with no page path set",
+                None
+            ),
+            Diagnostic::new(
+                DiagnosticKind::Unchecked,
+                "new-slug#top",
+                "Relative link new-slug#top not checked, with no page path set",
+                None
+            ),
+        ]
+    );
+
+    let mut manifest = SiteManifest::default();
+    manifest
+        .pages(HashMap::from([("/blog/new-slug", None), ("/about", None)]))
+        .page_path(Some("/blog/current-post"));
+    let document = Builder::new().site_manifest(Some(manifest)).process(html);
+    assert_eq!(
+        document.diagnostics(),
+        [Diagnostic::new(
+            DiagnosticKind::UnknownPage,
+            "old-slug",
+            "Link to unknown page /blog/old-slug",
+            None
+        )]
+    );
+}
+
+#[test]
+fn builder_rewrites_links_through_redirect_map() {
+    let mut manifest = SiteManifest::default();
+    manifest
+        .pages(HashMap::from([("/blog/new-slug", None)]))
+        .redirects(HashMap::from([("/blog/old-slug", "/blog/new-slug")]));
+    let document = Builder::new()
+        .canonical_root_url(Some("https://example.com"))
+        .site_manifest(Some(manifest))
+        .process(r#"<a href="/blog/old-slug/?ref=feed#top">Post</a>"#);
+    assert_eq!(
+        document.to_string(),
+        r#"<a href="https://example.com/blog/new-slug?ref=feed#top">Post</a>"#
+    );
+    assert_eq!(
+        document.diagnostics(),
+        [Diagnostic::new(
+            DiagnosticKind::Redirected,
+            "/blog/old-slug/?ref=feed#top",
+            "Link redirected from /blog/old-slug/?ref=feed#top to /blog/new-slug?ref=feed#top",
+            None
+        )]
+    );
+}
+
+#[test]
+fn builder_keeps_redirect_target_query_and_original_fragment() {
+    let mut manifest = SiteManifest::default();
+    manifest
+        .pages(HashMap::from([("/new", None), ("/blog/new-slug", None)]))
+        .redirects(HashMap::from([
+            ("/old", "/new?x=1"),
+            ("/blog/old-slug", "new-slug"),
+        ]))
+        .page_path(Some("/blog/current-post"));
+    let document = Builder::new()
+        .canonical_root_url(Some("https://example.com"))
+        .site_manifest(Some(manifest))
+        .process(r#"<a href="https://example.com/old?ref=feed#top">Old</a><a href="old-slug#intro">Post</a>"#);
+    assert_eq!(
+        document.to_string(),
+        r#"<a href="https://example.com/new?x=1#top">Old</a><a href="https://example.com/blog/new-slug#intro">Post</a>"#
+    );
+    assert_eq!(document.diagnostics().len(), 2);
+}
+
+#[test]
+fn builder_autolinks_bare_urls_outside_links_and_code() {
+    let result = Builder::new()
+        .autolink(true)
+        .canonical_root_url(Some("https://example.com"))
+        .process(r#"<p>Read https://example.org/guide. or www.example.com/about, not <code>https://example.net</code> or <a href="/x">https://example.edu</a>.</p>"#)
+        .to_string();
+    let expected = r#"<p>Read <a href="https://example.org/guide" target="_blank" rel="noopener noreferrer">https://example.org/guide</a>. or <a href="https://www.example.com/about">www.example.com/about</a>, not <code>https://example.net</code> or <a href="https://example.com/x">https://example.edu</a>.</p>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_obfuscates_email_addresses() {
+    let result = Builder::new()
+        .autolink(true)
+        .obfuscate_email(true)
+        .process("<p>Email a@b.io</p>")
+        .to_string();
+    let expected = r#"<p>Email <a href="&#109;&#97;&#105;&#108;&#116;&#111;&#58;&#97;&#64;&#98;&#46;&#105;&#111;" target="_blank" rel="noopener noreferrer">&#97;&#64;&#98;&#46;&#105;&#111;</a></p>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_obfuscates_only_exact_email_addresses_outside_code() {
+    let result = Builder::new()
+        .obfuscate_email(true)
+        .process(
+            r#"<p><a href="mailto:a@b.io?subject=Hi">Write</a> to a@b.io, not a@b.iox or xa@b.io</p><pre><code>a@b.io</code></pre><p><a href="https://example.com/a@b.io">Link</a></p>"#,
+        )
+        .to_string();
+    let expected = r#"<p><a href="&#109;&#97;&#105;&#108;&#116;&#111;&#58;&#97;&#64;&#98;&#46;&#105;&#111;&#63;&#115;&#117;&#98;&#106;&#101;&#99;&#116;&#61;&#72;&#105;" target="_blank" rel="noopener noreferrer">Write</a> to &#97;&#64;&#98;&#46;&#105;&#111;, not a@b.iox or xa@b.io</p><pre><code>a@b.io</code></pre><p><a href="https://example.com/a@b.io" target="_blank" rel="noopener noreferrer">Link</a></p>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_links_first_glossary_term_occurrence() {
+    let mut glossary = Glossary::default();
+    glossary.terms(vec![GlossaryTerm::new(
+        "Deno",
+        Some("JavaScript runtime"),
+        Some("https://deno.com"),
+    )]);
+    let result = Builder::new()
+        .glossary(glossary)
+        .process("<p>Deno, not Denoland.</p><p>Deno again.</p>")
+        .to_string();
+    let expected = r#"<p><a href="https://deno.com" title="JavaScript runtime" target="_blank" rel="noopener noreferrer">Deno</a>, not Denoland.</p><p>Deno again.</p>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_joins_last_words_and_number_units() {
+    let result = Builder::new()
+        .non_breaking_spaces(Some(NonBreakingSpaceRules::for_locale(Locale::English)))
+        .process(
+            "<h3>Trail running guide</h3><p>We ran 10 km before <em>the break</em></p><pre><code>let a = 10 km;</code></pre>",
+        )
+        .to_string();
+    let expected = "<h3>Trail running&nbsp;guide</h3><p>We ran 10&nbsp;km before <em>the&nbsp;break</em></p><pre><code>let a = 10 km;</code></pre>";
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_joins_single_letter_words_for_czech() {
+    let mut rules = NonBreakingSpaceRules::for_locale(Locale::Czech);
+    rules.widows(false);
+    let result = Builder::new()
+        .non_breaking_spaces(Some(rules))
+        .process("<p>Byli jsme v lese a u řeky, <code>v x</code></p>")
+        .to_string();
+    let expected = "<p>Byli jsme v&nbsp;lese a&nbsp;u&nbsp;řeky, <code>v x</code></p>";
+    assert_eq!(result, expected);
+
+    let result = Builder::new()
+        .non_breaking_spaces(Some(NonBreakingSpaceRules::for_locale(Locale::English)))
+        .process("<p>I went to a shop</p>")
+        .to_string();
+    assert_eq!(result, "<p>I went to a&nbsp;shop</p>");
+}
+
+#[test]
+fn builder_keeps_soft_hyphens_in_code_unencoded() {
+    let result = Builder::new()
+        .process("<p>In\u{ad}ter\u{ad}na\u{ad}tion\u{ad}al <code>in\u{ad}ter</code></p>")
+        .to_string();
+    let expected = "<p>In&shy;ter&shy;na&shy;tion&shy;al <code>in\u{ad}ter</code></p>";
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_hyphenates_long_words_in_paragraphs() {
+    let result = Builder::new()
+        .hyphenation(Hyphenation::for_language_tag("de-DE"))
+        .process(
+            "<h2>Donaudampfschifffahrtsgesellschaft</h2><p>Die Donaudampfschifffahrtsgesellschaft, <code>Donaudampfschifffahrtsgesellschaft</code> und https://example.com/Donaudampfschifffahrtsgesellschaft</p>",
+        )
+        .to_string();
+    let expected = "<h2>Donaudampfschifffahrtsgesellschaft</h2><p>Die Do&shy;nau&shy;dampf&shy;schiff&shy;fahrts&shy;ge&shy;sell&shy;schaft, <code>Donaudampfschifffahrtsgesellschaft</code> und https://example.com/Donaudampfschifffahrtsgesellschaft</p>";
+    assert_eq!(result, expected);
+
+    let mut hyphenation =
+        Hyphenation::for_language_tag("en").expect("English patterns should be bundled");
+    hyphenation.min_word_length(8);
+    let result = Builder::new()
+        .hyphenation(Some(hyphenation))
+        .process("<p>Internationalization matters, <em>mostly</em>.</p>")
+        .to_string();
+    let expected =
+        "<p>In&shy;ter&shy;na&shy;tion&shy;al&shy;iza&shy;tion matters, <em>mostly</em>.</p>";
+    assert_eq!(result, expected);
+
+    assert_eq!(Hyphenation::for_language_tag("ja"), None);
+    for tag in [
+        "cs", "da", "de-CH", "en-GB", "fi", "fr", "it-CH", "nb", "nn", "no", "pl", "sk", "sv",
+    ] {
+        assert!(
+            Hyphenation::for_language_tag(tag).is_some(),
+            "{tag} patterns should be bundled"
+        );
+    }
+}
+
+#[test]
+fn builder_labels_emoji_outside_code() {
+    let result = Builder::new()
+        .accessible_emoji(true)
+        .process(r#"<p>Done ✅ <code>🚀</code> <span role="img" aria-label="party">🎉</span></p>"#)
+        .to_string();
+    let expected = r#"<p>Done <span role="img" aria-label="check mark button">✅</span> <code>🚀</code> <span role="img" aria-label="party">🎉</span></p>"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn builder_wraps_sections_by_heading_level() {
+    let result = Builder::new()
+        .wrap_sections(true)
+        .process(r#"<h3 id="a">A</h3><p>1</p><h4 id="b">B</h4><p>2</p><h2 id="c">C</h2><h3>No id</h3><p>3</p>"#)
+        .to_string();
+    let expected = r##"<section aria-labelledby="a"><h3 id="a">A</h3><p>1</p><section aria-labelledby="b"><h4 id="b">B</h4><p>2</p></section></section><section aria-labelledby="c"><h2 id="c">C <a href="#c" class="heading-anchor">#</a></h2><h3>No id</h3><p>3</p></section>"##;
+    assert_eq!(result, expected);
+}
@@ -1,11 +1,11 @@
 use nom::{
     IResult, Parser,
     branch::alt,
-    bytes::complete::{is_not, tag},
-    character::complete::{alphanumeric1, multispace0},
-    combinator::recognize,
-    multi::many1_count,
-    sequence::{delimited, pair},
+    bytes::complete::{is_not, tag, take_till},
+    character::complete::{alphanumeric1, multispace0, multispace1},
+    combinator::{opt, recognize},
+    multi::{many0, many1_count},
+    sequence::{delimited, pair, preceded, terminated},
 };
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -25,6 +25,51 @@ fn parse_html_tag_content(line: &str) -> IResult<&str, (&str, &str)> {
     Ok((remainder, (tag_name, attributes)))
 }
 
+fn parse_attribute_value(line: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(tag("\""), take_till(|c| c == '"'), tag("\"")),
+        delimited(tag("'"), take_till(|c| c == '\''), tag("'")),
+        is_not(" \t\r\n\"'=<>`"),
+    ))
+    .parse(line)
+}
+
+/// Attribute name and optional value
+type Attribute<'a> = (&'a str, Option<&'a str>);
+
+/// Attribute name and optional value, following whitespace
+fn parse_attribute(line: &str) -> IResult<&str, Attribute<'_>> {
+    preceded(
+        multispace1,
+        pair(
+            recognize(many1_count(alt((
+                alphanumeric1,
+                tag("-"),
+                tag("_"),
+                tag(":"),
+            )))),
+            opt(preceded(
+                delimited(multispace0, tag("="), multispace0),
+                parse_attribute_value,
+            )),
+        ),
+    )
+    .parse(line)
+}
+
+/// Opening tag name and attributes, allowing for `/` in attribute values and self-closing tags
+fn parse_opening_html_tag_content(line: &str) -> IResult<&str, (&str, Vec<Attribute<'_>>)> {
+    delimited(
+        tag("<"),
+        pair(
+            recognize(many1_count(alt((alphanumeric1, tag("-"))))),
+            many0(parse_attribute),
+        ),
+        terminated(multispace0, pair(opt(tag("/")), tag(">"))),
+    )
+    .parse(line)
+}
+
 fn parse_closing_html_tag(line: &str) -> IResult<&str, (&str, &str, InlineHTMLTagType)> {
     let (remaining_line, (tag_name, tag_attributes)) =
         delimited(tag("</"), parse_html_tag_content, tag(">")).parse(line)?;
@@ -39,8 +84,11 @@ fn parse_closing_html_tag(line: &str) -> IResult<&str, (&str, &str, InlineHTMLTa
 }
 
 fn parse_opening_html_tag(line: &str) -> IResult<&str, (&str, &str, InlineHTMLTagType)> {
-    let (remaining_line, (tag_name, tag_attributes)) =
-        delimited(tag("<"), parse_html_tag_content, tag(">")).parse(line)?;
+    let (remaining_line, (tag_name, _attributes)) = parse_opening_html_tag_content(line)?;
+    let tag_attributes = line[1 + tag_name.len()..line.len() - remaining_line.len() - 1]
+        .trim()
+        .trim_end_matches('/')
+        .trim_end();
     Ok((
         remaining_line,
         (
@@ -58,11 +106,21 @@ pub fn parse_node(html_node: &str) -> Option<InlineHTMLTagType> {
     }
 }
 
+/// Value of the `name` attribute in the opening tag `html_node`, empty for an attribute with no
+/// value
+pub fn attribute_value(html_node: &str, name: &str) -> Option<String> {
+    let (_, (_, attributes)) = parse_opening_html_tag_content(html_node).ok()?;
+    attributes
+        .into_iter()
+        .find(|(attribute_name, _)| attribute_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.unwrap_or_default().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        InlineHTMLTagType, parse_closing_html_tag, parse_html_tag_content, parse_node,
-        parse_opening_html_tag,
+        InlineHTMLTagType, attribute_value, parse_closing_html_tag, parse_html_tag_content,
+        parse_node, parse_opening_html_tag,
     };
 
     #[test]
@@ -181,6 +239,44 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_opening_html_tag_parses_attribute_values_with_slashes() {
+        // arrange
+        let tag = r#"<a href="https://example.com/" class=external>"#;
+
+        // act
+        let result = parse_opening_html_tag(tag);
+
+        // assert
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                (
+                    "a",
+                    r#"href="https://example.com/" class=external"#,
+                    InlineHTMLTagType::Opening(String::from("a"))
+                )
+            ))
+        );
+    }
+
+    #[test]
+    pub fn attribute_value_returns_named_attribute() {
+        // arrange
+        let tag = r"<abbr title='Command Line Interface' hidden>";
+
+        // act
+        let title = attribute_value(tag, "title");
+        let hidden = attribute_value(tag, "hidden");
+        let missing = attribute_value(tag, "lang");
+
+        // assert
+        assert_eq!(title, Some(String::from("Command Line Interface")));
+        assert_eq!(hidden, Some(String::new()));
+        assert_eq!(missing, None);
+    }
+
     #[test]
     pub fn parse_node_returns_none_for_invalid_html_tag() {
         // arrange
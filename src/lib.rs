@@ -3,18 +3,29 @@
 mod html_process;
 mod inline_html;
 mod markdown;
+mod text;
 mod url_utility;
 mod utilities;
 
+use std::{borrow::Cow, collections::HashMap};
+
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::{JsValue, prelude::*};
 
 use crate::{
-    html_process::process_html,
+    html_process::{
+        Builder, Diagnostic, Glossary, GlossaryTerm, Hyphenation, Image, Link,
+        NonBreakingSpaceRules, SiteManifest, markdown_html_builder,
+    },
     markdown::{
-        Heading, ParseMarkdownOptions, TextStatistics, parse_markdown_to_html,
-        parse_markdown_to_plaintext,
+        CrossReference, CrossReferenceTemplates, Excerpt, Heading, HeadingOnePolicy,
+        HtmlElementRule, ParseMarkdownOptions, PlaintextEmphasisStyle, PlaintextHeadingStyle,
+        PlaintextLinkStyle, TextStatistics, extract_abbreviations, link_source_lines,
+        markdown_excerpt, parse_html_to_markdown, parse_html_to_plaintext, parse_markdown_to_html,
+        parse_markdown_to_markdown, parse_markdown_to_mdast, parse_markdown_to_plaintext,
     },
+    text::Locale,
+    url_utility::{QueryParameterRule, UrlRewriter},
 };
 
 #[wasm_bindgen]
@@ -29,11 +40,277 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
-#[derive(Deserialize)]
+#[derive(Default, Deserialize)]
 pub struct ParseInputOptions {
     canonical_root_url: Option<String>,
     enable_smart_punctuation: Option<bool>,
     search_term: Option<String>,
+    internal_domains: Option<Vec<String>>,
+    sponsored_domains: Option<Vec<String>>,
+    ugc_domains: Option<Vec<String>>,
+    external_links_new_tab: Option<bool>,
+    append_query_parameters: Option<Vec<QueryParameterInput>>,
+    strip_query_parameters: Option<Vec<String>>,
+    known_pages: Option<HashMap<String, Option<Vec<String>>>>,
+    redirects: Option<HashMap<String, String>>,
+
+    /// Path of the page being processed, for checking document-relative links
+    page_path: Option<String>,
+    enable_autolink: Option<bool>,
+    obfuscate_email: Option<bool>,
+    wiki_link_template: Option<String>,
+    mention_template: Option<String>,
+    hashtag_template: Option<String>,
+    enable_gfm: Option<bool>,
+    glossary: Option<Vec<GlossaryInput>>,
+    enable_abbreviations: Option<bool>,
+    locale: Option<String>,
+    non_breaking_spaces: Option<bool>,
+
+    /// Overrides for the `locale` non-breaking space rules, used with `non_breaking_spaces`
+    join_number_units: Option<bool>,
+    join_single_letter_words: Option<bool>,
+    join_widows: Option<bool>,
+    hyphenate: Option<bool>,
+    hyphenation_min_word_length: Option<usize>,
+    enable_emoji_shortcodes: Option<bool>,
+    accessible_emoji: Option<bool>,
+    drop_emoji_from_slugs: Option<bool>,
+    heading_level_offset: Option<u8>,
+    heading_one_policy: Option<HeadingOnePolicy>,
+    wrap_sections: Option<bool>,
+    excerpt_words: Option<usize>,
+    description_length: Option<usize>,
+
+    /// Plaintext line length, with `0` leaving lines unwrapped
+    line_length: Option<usize>,
+    link_style: Option<PlaintextLinkStyle>,
+    heading_style: Option<PlaintextHeadingStyle>,
+    emphasis_style: Option<PlaintextEmphasisStyle>,
+    label_images: Option<bool>,
+
+    /// Inline HTML elements left out of plaintext, defaults to `tool-tip`
+    ignore_tags: Option<Vec<String>>,
+    html_element_rules: Option<Vec<HtmlElementRuleInput>>,
+}
+
+#[derive(Deserialize)]
+pub struct GlossaryInput {
+    term: String,
+    definition: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum HtmlElementAction {
+    Drop,
+    KeepText,
+    Attribute,
+}
+
+#[derive(Deserialize)]
+pub struct HtmlElementRuleInput {
+    tag: String,
+    action: HtmlElementAction,
+    attribute: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct QueryParameterInput {
+    domain: String,
+    parameters: Vec<(String, String)>,
+}
+
+/// Converts optional, owned, string lists from input options into borrowed slices
+fn string_slices(value: Option<&Vec<String>>) -> Vec<&str> {
+    value.map_or_else(Vec::new, |domains| {
+        domains.iter().map(String::as_str).collect()
+    })
+}
+
+fn url_rewriter(options: &ParseInputOptions) -> UrlRewriter<'_> {
+    let mut rewriter = UrlRewriter::default();
+    if let Some(rules) = &options.append_query_parameters {
+        rewriter.append_parameters(
+            rules
+                .iter()
+                .map(|QueryParameterInput { domain, parameters }| {
+                    QueryParameterRule::new(
+                        domain,
+                        parameters
+                            .iter()
+                            .map(|(name, value)| (name.as_str(), value.as_str()))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        );
+    }
+    rewriter.strip_parameters(string_slices(options.strip_query_parameters.as_ref()));
+    rewriter
+}
+
+fn site_manifest(options: &ParseInputOptions) -> Option<SiteManifest<'_>> {
+    if options.known_pages.is_none() && options.redirects.is_none() {
+        return None;
+    }
+    let mut manifest = SiteManifest::default();
+    manifest.page_path(options.page_path.as_deref());
+    if let Some(pages) = &options.known_pages {
+        manifest.pages(
+            pages
+                .iter()
+                .map(|(path, ids)| {
+                    (
+                        path.as_str(),
+                        ids.as_ref()
+                            .map(|ids| ids.iter().map(String::as_str).collect()),
+                    )
+                })
+                .collect(),
+        );
+    }
+    if let Some(redirects) = &options.redirects {
+        manifest.redirects(
+            redirects
+                .iter()
+                .map(|(from, to)| (from.as_str(), to.as_str()))
+                .collect(),
+        );
+    }
+    Some(manifest)
+}
+
+/// Plaintext HTML element rules, with explicit rules taking precedence over ignored tags
+fn html_element_rules(options: &ParseInputOptions) -> Vec<(&str, HtmlElementRule<'_>)> {
+    let mut rules: Vec<(&str, HtmlElementRule)> = options
+        .html_element_rules
+        .iter()
+        .flatten()
+        .map(
+            |HtmlElementRuleInput {
+                 tag,
+                 action,
+                 attribute,
+             }| {
+                let rule = match (action, attribute) {
+                    (HtmlElementAction::Drop, _) => HtmlElementRule::Drop,
+                    (HtmlElementAction::Attribute, Some(attribute)) => {
+                        HtmlElementRule::Attribute(attribute)
+                    }
+                    (HtmlElementAction::KeepText | HtmlElementAction::Attribute, _) => {
+                        HtmlElementRule::KeepText
+                    }
+                };
+                (tag.as_str(), rule)
+            },
+        )
+        .collect();
+    match &options.ignore_tags {
+        Some(tags) => rules.extend(tags.iter().map(|tag| (tag.as_str(), HtmlElementRule::Drop))),
+        None => rules.push(("tool-tip", HtmlElementRule::Drop)),
+    }
+    rules
+}
+
+fn glossary<'a>(
+    options: &'a ParseInputOptions,
+    abbreviations: &'a [(String, String)],
+) -> Glossary<'a> {
+    let mut glossary = Glossary::default();
+    if let Some(terms) = &options.glossary {
+        glossary.terms(
+            terms
+                .iter()
+                .map(
+                    |GlossaryInput {
+                         term,
+                         definition,
+                         url,
+                     }| {
+                        GlossaryTerm::new(term, definition.as_deref(), url.as_deref())
+                    },
+                )
+                .collect(),
+        );
+    }
+    glossary.abbreviations(
+        abbreviations
+            .iter()
+            .map(|(abbreviation, expansion)| (abbreviation.as_str(), expansion.as_str()))
+            .collect(),
+    );
+    glossary
+}
+
+/// Markdown with any abbreviation definitions removed, when they are enabled, and the definitions
+fn markdown_abbreviations<'a>(
+    markdown: &'a str,
+    options: &ParseInputOptions,
+) -> (Cow<'a, str>, Vec<(String, String)>) {
+    if options.enable_abbreviations.unwrap_or(false) {
+        let (value, abbreviations) = extract_abbreviations(markdown);
+        (Cow::Owned(value), abbreviations)
+    } else {
+        (Cow::Borrowed(markdown), Vec::new())
+    }
+}
+
+fn non_breaking_space_rules(options: &ParseInputOptions) -> Option<NonBreakingSpaceRules> {
+    if options.non_breaking_spaces == Some(true) {
+        let locale = options
+            .locale
+            .as_deref()
+            .map_or(Locale::English, Locale::from_language_tag);
+        let mut rules = NonBreakingSpaceRules::for_locale(locale);
+        if let Some(value) = options.join_number_units {
+            rules.number_units(value);
+        }
+        if let Some(value) = options.join_single_letter_words {
+            rules.single_letter_words(value);
+        }
+        if let Some(value) = options.join_widows {
+            rules.widows(value);
+        }
+        Some(rules)
+    } else {
+        None
+    }
+}
+
+fn hyphenation(options: &ParseInputOptions) -> Option<Hyphenation> {
+    if options.hyphenate != Some(true) {
+        return None;
+    }
+    let mut hyphenation = Hyphenation::for_language_tag(options.locale.as_deref().unwrap_or("en"))?;
+    if let Some(value) = options.hyphenation_min_word_length {
+        hyphenation.min_word_length(value);
+    }
+    Some(hyphenation)
+}
+
+fn html_builder(options: &ParseInputOptions) -> Builder<'_> {
+    let mut builder = markdown_html_builder(
+        options.canonical_root_url.as_deref(),
+        options.search_term.as_deref(),
+    );
+    builder
+        .internal_domains(string_slices(options.internal_domains.as_ref()))
+        .sponsored_domains(string_slices(options.sponsored_domains.as_ref()))
+        .ugc_domains(string_slices(options.ugc_domains.as_ref()))
+        .url_rewriter(url_rewriter(options))
+        .site_manifest(site_manifest(options))
+        .autolink(options.enable_autolink.unwrap_or(false))
+        .obfuscate_email(options.obfuscate_email.unwrap_or(false))
+        .non_breaking_spaces(non_breaking_space_rules(options))
+        .hyphenation(hyphenation(options))
+        .accessible_emoji(options.accessible_emoji.unwrap_or(false))
+        .wrap_sections(options.wrap_sections.unwrap_or(false));
+    if options.external_links_new_tab == Some(false) {
+        builder.link_target(None);
+    }
+    builder
 }
 
 #[derive(Debug, Eq, PartialEq, Serialize)]
@@ -41,23 +318,82 @@ pub struct ParseResults {
     html: Option<String>,
     headings: Option<Vec<Heading>>,
     statistics: Option<TextStatistics>,
+    excerpt: Option<Excerpt>,
+    links: Option<Vec<Link>>,
+    images: Option<Vec<Image>>,
+    diagnostics: Option<Vec<Diagnostic>>,
+    cross_references: Option<Vec<CrossReference>>,
     errors: Option<Vec<String>>,
 }
 
+fn markdown_options(options: &ParseInputOptions) -> ParseMarkdownOptions<'_> {
+    let mut markdown_options = ParseMarkdownOptions::default();
+    markdown_options
+        .canonical_root_url(options.canonical_root_url.as_deref())
+        .url_rewriter(url_rewriter(options))
+        .cross_reference_templates(CrossReferenceTemplates {
+            wiki_link: options.wiki_link_template.as_deref(),
+            mention: options.mention_template.as_deref(),
+            hashtag: options.hashtag_template.as_deref(),
+        });
+    if let Some(value) = options.enable_smart_punctuation {
+        markdown_options.enable_smart_punctuation(value);
+    }
+    markdown_options
+        .locale(options.locale.as_deref().map(Locale::from_language_tag))
+        .enable_gfm(options.enable_gfm.unwrap_or(false))
+        .enable_emoji_shortcodes(options.enable_emoji_shortcodes.unwrap_or(false))
+        .drop_emoji_from_slugs(options.drop_emoji_from_slugs.unwrap_or(false))
+        .heading_level_offset(options.heading_level_offset.unwrap_or(0))
+        .heading_one_policy(options.heading_one_policy.unwrap_or_default())
+        .link_style(options.link_style.unwrap_or_default())
+        .heading_style(options.heading_style.unwrap_or_default())
+        .emphasis_style(options.emphasis_style.unwrap_or_default())
+        .label_images(options.label_images.unwrap_or(false))
+        .html_element_rules(html_element_rules(options));
+    if let Some(value) = options.excerpt_words {
+        markdown_options.excerpt_words(value);
+    }
+    if let Some(value) = options.description_length {
+        markdown_options.description_length(value);
+    }
+    if let Some(value) = options.line_length {
+        markdown_options.line_length((value > 0).then_some(value));
+    }
+    markdown_options
+}
+
+/// Excerpt with its HTML links processed like the document, but with no search highlighting
+fn processed_excerpt(markdown: &str, options: &ParseInputOptions) -> Excerpt {
+    let mut excerpt = markdown_excerpt(markdown, &markdown_options(options));
+    let mut builder = html_builder(options);
+    builder.search_term(None).wrap_sections(false);
+    let html = builder.process(excerpt.html()).to_string();
+    excerpt.set_html(html);
+    excerpt
+}
+
 fn markdown_to_processed_html(markdown: &str, options: &ParseInputOptions) -> ParseResults {
-    match parse_markdown_to_html(markdown) {
-        Ok((html_value, headings, statistics_value)) => {
-            let html = Some(process_html(
-                &html_value,
-                options.canonical_root_url.as_deref(),
-                options.search_term.as_deref(),
-            ));
+    let (markdown, abbreviations) = markdown_abbreviations(markdown, options);
+    match parse_markdown_to_html(&markdown, &markdown_options(options)) {
+        Ok((html_value, headings, statistics_value, cross_references)) => {
+            let mut builder = html_builder(options);
+            builder.glossary(glossary(options, &abbreviations));
+            let mut document = builder.process(&html_value);
+            document.set_link_source_lines(&link_source_lines(&markdown));
+            let html = Some(document.to_string());
             let headings = Some(headings);
             let statistics = Some(statistics_value);
+            let excerpt = Some(processed_excerpt(&markdown, options));
             ParseResults {
                 html,
                 headings,
                 statistics,
+                excerpt,
+                links: Some(document.links().to_vec()),
+                images: Some(document.images().to_vec()),
+                diagnostics: Some(document.diagnostics().to_vec()),
+                cross_references: Some(cross_references),
                 errors: None,
             }
         }
@@ -68,6 +404,11 @@ fn markdown_to_processed_html(markdown: &str, options: &ParseInputOptions) -> Pa
                 html: None,
                 headings: None,
                 statistics: None,
+                excerpt: None,
+                links: None,
+                images: None,
+                diagnostics: None,
+                cross_references: None,
                 errors: Some(errors),
             }
         }
@@ -84,9 +425,8 @@ pub fn markdown_to_html(markdown: &str, options: JsValue) -> JsValue {
     let parse_options = match input_options {
         Some(value) => value,
         None => ParseInputOptions {
-            canonical_root_url: None,
             enable_smart_punctuation: Some(true),
-            search_term: None,
+            ..ParseInputOptions::default()
         },
     };
     let results = markdown_to_processed_html(markdown, &parse_options);
@@ -100,26 +440,63 @@ pub fn markdown_to_html(markdown: &str, options: JsValue) -> JsValue {
 #[must_use]
 pub fn markdown_to_plaintext(markdown: &str, options: JsValue) -> String {
     let input_options: Option<ParseInputOptions> = serde_wasm_bindgen::from_value(options).unwrap();
-    let mut markdown_options = ParseMarkdownOptions::default();
-
-    if let Some(ParseInputOptions {
-        canonical_root_url,
-        enable_smart_punctuation,
-        ..
-    }) = input_options
-    {
-        let canonical_root_url = canonical_root_url.as_deref();
-        markdown_options.canonical_root_url(canonical_root_url);
-
-        if let Some(value) = enable_smart_punctuation {
-            markdown_options.enable_smart_punctuation(value);
+    match &input_options {
+        Some(value) => {
+            let (markdown, _abbreviations) = markdown_abbreviations(markdown, value);
+            parse_markdown_to_plaintext(&markdown, &markdown_options(value))
         }
-        parse_markdown_to_plaintext(markdown, &markdown_options)
-    } else {
-        parse_markdown_to_plaintext(markdown, &markdown_options)
+        None => parse_markdown_to_plaintext(markdown, &ParseMarkdownOptions::default()),
+    }
+}
+
+/// # Panics
+///
+/// Will panic if unable to parse options
+#[wasm_bindgen]
+#[must_use]
+pub fn html_to_plaintext(html: &str, options: JsValue) -> String {
+    let input_options: Option<ParseInputOptions> = serde_wasm_bindgen::from_value(options).unwrap();
+    match &input_options {
+        Some(value) => parse_html_to_plaintext(html, &markdown_options(value)),
+        None => parse_html_to_plaintext(html, &ParseMarkdownOptions::default()),
+    }
+}
+
+/// # Panics
+///
+/// Will panic if unable to parse options
+#[wasm_bindgen]
+#[must_use]
+pub fn markdown_to_mdast(markdown: &str, options: JsValue) -> JsValue {
+    let input_options: Option<ParseInputOptions> = serde_wasm_bindgen::from_value(options).unwrap();
+    let tree = match &input_options {
+        Some(value) => parse_markdown_to_mdast(markdown, &markdown_options(value)),
+        None => parse_markdown_to_mdast(markdown, &ParseMarkdownOptions::default()),
+    };
+    // mdast uses `null`, rather than a missing field, for absent optional values
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_missing_as_null(true);
+    tree.serialize(&serializer).unwrap()
+}
+
+/// # Panics
+///
+/// Will panic if unable to parse options
+#[wasm_bindgen]
+#[must_use]
+pub fn markdown_to_markdown(markdown: &str, options: JsValue) -> String {
+    let input_options: Option<ParseInputOptions> = serde_wasm_bindgen::from_value(options).unwrap();
+    match &input_options {
+        Some(value) => parse_markdown_to_markdown(markdown, &markdown_options(value)),
+        None => parse_markdown_to_markdown(markdown, &ParseMarkdownOptions::default()),
     }
 }
 
+#[wasm_bindgen]
+#[must_use]
+pub fn html_to_markdown(html: &str) -> String {
+    parse_html_to_markdown(html)
+}
+
 #[wasm_bindgen]
 #[must_use]
 pub fn mjml_to_html(mjml: &str) -> String {
@@ -143,8 +520,10 @@ pub fn mjml_to_html(mjml: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{html_process::LinkKind, markdown::cross_reference::CrossReferenceKind};
 
     #[test]
+    #[allow(clippy::too_many_lines)]
     fn test_markdown_to_html() {
         let markdown = r"
 hello you
@@ -160,6 +539,7 @@ hello you
                 canonical_root_url: None,
                 enable_smart_punctuation: Some(true),
                 search_term: None,
+                ..ParseInputOptions::default()
             },
         );
         let html = Some(String::from(
@@ -174,8 +554,17 @@ hello you
             result,
             ParseResults {
                 html,
-                headings: Some(vec![Heading::new("hello you", "hello-you")]),
+                headings: Some(vec![Heading::new("hello you", "hello-you", 1)]),
                 statistics: Some(TextStatistics::new(4)),
+                excerpt: Some(Excerpt::new(
+                    "<ul>\n<li>alpha</li>\n<li>beta</li>\n</ul>\n",
+                    "alpha\nbeta",
+                    "alpha beta"
+                )),
+                links: Some(Vec::new()),
+                images: Some(Vec::new()),
+                diagnostics: Some(Vec::new()),
+                cross_references: Some(Vec::new()),
                 errors: None
             }
         );
@@ -192,6 +581,7 @@ Paragraph text.
                 canonical_root_url: None,
                 enable_smart_punctuation: Some(true),
                 search_term: None,
+                ..ParseInputOptions::default()
             },
         );
         let html = Some(String::from(
@@ -203,8 +593,17 @@ Paragraph text.
             result,
             ParseResults {
                 html,
-                headings: Some(vec![Heading::new("Subheading", "subheading")]),
+                headings: Some(vec![Heading::new("Subheading", "subheading", 2)]),
                 statistics: Some(TextStatistics::new(3)),
+                excerpt: Some(Excerpt::new(
+                    "<p>Paragraph text.</p>\n",
+                    "Paragraph text.",
+                    "Paragraph text."
+                )),
+                links: Some(Vec::new()),
+                images: Some(Vec::new()),
+                diagnostics: Some(Vec::new()),
+                cross_references: Some(Vec::new()),
                 errors: None
             },
         );
@@ -221,6 +620,7 @@ Link: [Example site](https://example.com).
                 canonical_root_url: None,
                 enable_smart_punctuation: Some(true),
                 search_term: None,
+                ..ParseInputOptions::default()
             },
         );
         let html = Some(String::from(
@@ -232,13 +632,348 @@ Link: [Example site](https://example.com).
             result,
             ParseResults {
                 html,
-                headings: Some(vec![Heading::new("Subheading", "subheading")]),
+                headings: Some(vec![Heading::new("Subheading", "subheading", 3)]),
                 statistics: Some(TextStatistics::new(4)),
+                excerpt: Some(Excerpt::new(
+                    "<p>Link: <a href=\"https://example.com\" target=\"_blank\" rel=\"nofollow noopener noreferrer\">Example site</a>.</p>\n",
+                    "Link: Example site.",
+                    "Link: Example site."
+                )),
+                links: Some(vec![Link::new(
+                    "https://example.com",
+                    "Example site",
+                    LinkKind::External,
+                    Some(4)
+                )]),
+                images: Some(Vec::new()),
+                diagnostics: Some(Vec::new()),
+                cross_references: Some(Vec::new()),
                 errors: None
             }
         );
     }
 
+    #[test]
+    fn markdown_to_processed_html_collects_links_and_images() {
+        let markdown = r#"## Gallery
+
+![Sunset over the bay](/images/sunset.jpg "Evening")
+
+See [below](#notes), the [about page](/about) and
+[the source](https://example.org).
+"#;
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                canonical_root_url: Some(String::from("https://example.com")),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.links,
+            Some(vec![
+                Link::new(
+                    "https://example.com#notes",
+                    "below",
+                    LinkKind::Fragment,
+                    Some(5)
+                ),
+                Link::new(
+                    "https://example.com/about",
+                    "about page",
+                    LinkKind::Internal,
+                    Some(5)
+                ),
+                Link::new(
+                    "https://example.org",
+                    "the source",
+                    LinkKind::External,
+                    Some(6)
+                ),
+            ])
+        );
+        assert_eq!(
+            result.images,
+            Some(vec![Image::new(
+                "/images/sunset.jpg",
+                Some("Sunset over the bay"),
+                Some("Evening")
+            )])
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_resolves_cross_references() {
+        let markdown = "See [[Getting Started|the guide]] and [[Release Notes#Version 2]], ask @rodney about #web-dev.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                wiki_link_template: Some(String::from("/wiki/{slug}")),
+                mention_template: Some(String::from("/people/{slug}")),
+                hashtag_template: Some(String::from("/topics/{slug}")),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                r#"<p>See <a href="/wiki/getting-started" class="wiki-link">the guide</a> and <a href="/wiki/release-notes#version-2" class="wiki-link">Release Notes#Version 2</a>, ask <a href="/people/rodney" class="mention">@rodney</a> about <a href="/topics/web-dev" class="hashtag">#web-dev</a>.</p>
+"#
+            ))
+        );
+        assert_eq!(
+            result.cross_references,
+            Some(vec![
+                CrossReference::new(
+                    CrossReferenceKind::WikiLink,
+                    "Getting Started",
+                    "/wiki/getting-started"
+                ),
+                CrossReference::new(
+                    CrossReferenceKind::WikiLink,
+                    "Release Notes#Version 2",
+                    "/wiki/release-notes#version-2"
+                ),
+                CrossReference::new(CrossReferenceKind::Mention, "rodney", "/people/rodney"),
+                CrossReference::new(CrossReferenceKind::Hashtag, "web-dev", "/topics/web-dev"),
+            ])
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_applies_glossary_and_abbreviations() {
+        let markdown = r"## Using WASM
+
+WASM and HTML run in the browser, WASM is fast. `WASM` in code is unchanged.
+
+*[HTML]: Hyper Text Markup Language
+";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                glossary: Some(vec![GlossaryInput {
+                    term: String::from("WASM"),
+                    definition: Some(String::from("WebAssembly")),
+                    url: None,
+                }]),
+                enable_abbreviations: Some(true),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                r##"<h2 id="using-wasm">Using WASM <a href="#using-wasm" class="heading-anchor">#</a></h2>
+<p><abbr title="WebAssembly">WASM</abbr> and <abbr title="Hyper Text Markup Language">HTML</abbr> run in the browser, WASM is fast. <code>WASM</code> in code is unchanged.</p>
+"##
+            ))
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_applies_locale_typography() {
+        let markdown = "## \"Zitat\"\n\nEr sagte \"*sehr* gut\", Seiten 10-12.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                locale: Some(String::from("de-DE")),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                "<h2 id=\"zitat&quot;\">„Zitat“ <a href=\"#zitat&quot;\" class=\"heading-anchor\">#</a></h2>\n<p>Er sagte „<em>sehr</em> gut“, Seiten 10–12.</p>\n"
+            ))
+        );
+        assert_eq!(
+            result.headings,
+            Some(vec![Heading::new("„Zitat“", "zitat\"", 2)])
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_adds_non_breaking_spaces() {
+        let markdown = "## Cesta do hor\n\nŠli jsme 5 km v dešti.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                locale: Some(String::from("cs")),
+                non_breaking_spaces: Some(true),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                "<h2 id=\"cesta-do-hor\">Cesta do&nbsp;hor <a href=\"#cesta-do-hor\" class=\"heading-anchor\">#</a></h2>\n<p>Šli jsme 5&nbsp;km v&nbsp;dešti.</p>\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_overrides_non_breaking_space_rules() {
+        let markdown = "## Cesta do hor\n\nŠli jsme 5 km v dešti.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                locale: Some(String::from("cs")),
+                non_breaking_spaces: Some(true),
+                join_number_units: Some(false),
+                join_single_letter_words: Some(false),
+                join_widows: Some(false),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                "<h2 id=\"cesta-do-hor\">Cesta do hor <a href=\"#cesta-do-hor\" class=\"heading-anchor\">#</a></h2>\n<p>Šli jsme 5 km v dešti.</p>\n"
+            ))
+        );
+
+        let result = markdown_to_processed_html(
+            "We walked 5 km to a village.\n",
+            &ParseInputOptions {
+                non_breaking_spaces: Some(true),
+                join_single_letter_words: Some(true),
+                join_widows: Some(false),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                "<p>We walked 5&nbsp;km to a&nbsp;village.</p>\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_hyphenates_after_search_matching() {
+        let markdown = "Die Donaudampfschifffahrtsgesellschaft fährt.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                search_term: Some(String::from("dampfschiff")),
+                locale: Some(String::from("de")),
+                hyphenate: Some(true),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                "<p>Die Do&shy;nau<mark id=\"search-match\">&shy;dampf&shy;schiff</mark>&shy;fahrts&shy;ge&shy;sell&shy;schaft fährt.</p>\n"
+            ))
+        );
+        assert_eq!(result.statistics, Some(TextStatistics::new(3)));
+    }
+
+    #[test]
+    fn markdown_to_processed_html_expands_emoji_shortcodes() {
+        let markdown = "## Launch :rocket:\n\nWe have lift-off :rocket: and `:rocket:`, but :unknown: stays.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                enable_emoji_shortcodes: Some(true),
+                accessible_emoji: Some(true),
+                drop_emoji_from_slugs: Some(true),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                r##"<h2 id="launch">Launch <span role="img" aria-label="rocket">🚀</span> <a href="#launch" class="heading-anchor">#</a></h2>
+<p>We have lift-off <span role="img" aria-label="rocket">🚀</span> and <code>:rocket:</code>, but :unknown: stays.</p>
+"##
+            ))
+        );
+        assert_eq!(
+            result.headings,
+            Some(vec![Heading::new("Launch 🚀", "launch", 2)])
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_adjusts_heading_levels() {
+        let markdown = "# Overview\n\n###### Details\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                heading_level_offset: Some(1),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                r##"<h2 id="overview">Overview <a href="#overview" class="heading-anchor">#</a></h2>
+<h6 id="details">Details</h6>
+"##
+            ))
+        );
+        assert_eq!(
+            result.headings,
+            Some(vec![
+                Heading::new("Overview", "overview", 2),
+                Heading::new("Details", "details", 6)
+            ])
+        );
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                heading_one_policy: Some(HeadingOnePolicy::Reject),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(result.html, None);
+        assert_eq!(
+            result.errors,
+            Some(vec![String::from(
+                "Error parsing markdown: Level one heading \"Overview\" is not allowed in the document body"
+            )])
+        );
+    }
+
+    #[test]
+    fn markdown_to_processed_html_wraps_sections() {
+        let markdown =
+            "Intro.\n\n## Install\n\nRun it.\n\n### Linux\n\nUse apt.\n\n## Usage\n\nCall it.\n";
+
+        let result = markdown_to_processed_html(
+            markdown,
+            &ParseInputOptions {
+                wrap_sections: Some(true),
+                ..ParseInputOptions::default()
+            },
+        );
+        assert_eq!(
+            result.html,
+            Some(String::from(
+                r##"<p>Intro.</p>
+<section aria-labelledby="install"><h2 id="install">Install <a href="#install" class="heading-anchor">#</a></h2>
+<p>Run it.</p>
+<section aria-labelledby="linux"><h3 id="linux">Linux</h3>
+<p>Use apt.</p>
+</section></section><section aria-labelledby="usage"><h2 id="usage">Usage <a href="#usage" class="heading-anchor">#</a></h2>
+<p>Call it.</p>
+</section>"##
+            ))
+        );
+    }
+
     #[test]
     fn test_mjml_to_html() {
         let mjml = r#"<mjml lang="en-GB"></mjml>"#;
@@ -0,0 +1,84 @@
+use nom::{
+    IResult, Parser,
+    bytes::complete::{is_not, tag},
+    character::complete::space0,
+    combinator::rest,
+    sequence::{delimited, preceded},
+};
+
+/// Parses a PHP Markdown Extra abbreviation definition line: `*[HTML]: Hyper Text Markup Language`
+fn parse_abbreviation_definition(line: &str) -> IResult<&str, (&str, &str)> {
+    let (remainder, abbreviation) =
+        preceded(space0, delimited(tag("*["), is_not("]"), tag("]:"))).parse(line)?;
+    let (remainder, expansion) = preceded(space0, rest).parse(remainder)?;
+    Ok((remainder, (abbreviation, expansion.trim_end())))
+}
+
+/// Removes abbreviation definitions, outside fenced code, from `markdown`.  Each definition line
+/// is left blank, so source line numbers are unchanged.  Returns the remaining Markdown, together
+/// with the abbreviations and their expansions.
+pub fn extract_abbreviations(markdown: &str) -> (String, Vec<(String, String)>) {
+    let mut result = String::with_capacity(markdown.len());
+    let mut abbreviations = Vec::new();
+    let mut open_fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed_line = line.trim_start();
+        if let Some(fence) = open_fence {
+            if trimmed_line.starts_with(fence) {
+                open_fence = None;
+            }
+        } else if trimmed_line.starts_with("```") {
+            open_fence = Some("```");
+        } else if trimmed_line.starts_with("~~~") {
+            open_fence = Some("~~~");
+        } else if let Ok((_, (abbreviation, expansion))) = parse_abbreviation_definition(line) {
+            if !expansion.is_empty() {
+                abbreviations.push((abbreviation.to_string(), expansion.to_string()));
+                if line.ends_with('\n') {
+                    result.push('\n');
+                }
+                continue;
+            }
+        }
+        result.push_str(line);
+    }
+    (result, abbreviations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_abbreviations, parse_abbreviation_definition};
+
+    #[test]
+    pub fn parse_abbreviation_definition_parses_valid_definition() {
+        // arrange
+        let line = "*[HTML]: Hyper Text Markup Language\n";
+
+        // act
+        let result = parse_abbreviation_definition(line);
+
+        // assert
+        assert_eq!(result, Ok(("", ("HTML", "Hyper Text Markup Language"))));
+    }
+
+    #[test]
+    pub fn extract_abbreviations_blanks_definitions_outside_code() {
+        // arrange
+        let markdown = "HTML and CSS.\n\n*[HTML]: Hyper Text Markup Language\n\n```\n*[CSS]: Cascading Style Sheets\n```\n";
+
+        // act
+        let result = extract_abbreviations(markdown);
+
+        // assert
+        assert_eq!(
+            result,
+            (
+                String::from("HTML and CSS.\n\n\n\n```\n*[CSS]: Cascading Style Sheets\n```\n"),
+                vec![(
+                    String::from("HTML"),
+                    String::from("Hyper Text Markup Language")
+                )]
+            )
+        );
+    }
+}
@@ -0,0 +1,853 @@
+mod html;
+
+use std::borrow::Cow;
+
+use pulldown_cmark::{
+    Alignment, CodeBlockKind, CowStr,
+    Event::{
+        self, Code, End, HardBreak, Html, InlineHtml, Rule, SoftBreak, Start, TaskListMarker, Text,
+    },
+    HeadingLevel, LinkType, Tag, TagEnd,
+};
+use textwrap::{Options, WordSeparator, WordSplitter, core::display_width, wrap};
+
+use super::plaintext::UNBREAKABLE_SPACE;
+use html::html_events;
+
+/// Block containing the text currently being written, which adds a prefix to each output line
+enum Container {
+    BlockQuote,
+    List {
+        /// Number for the next item of an ordered list
+        next_number: Option<u64>,
+
+        /// `-` or `*` for bullet lists, `.` or `)` for ordered ones, alternated for adjacent lists
+        /// so they are not merged
+        delimiter: char,
+
+        /// Whether items are separated by blank lines
+        loose: bool,
+
+        /// Whether an item has been started
+        started: bool,
+    },
+    Item {
+        marker: String,
+
+        /// Whether the marker is still to be written, on the first line of the item
+        marker_pending: bool,
+    },
+}
+
+/// Rows of the current table, with the cell being written kept in the current line
+struct Table {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Link or image destination, title and kind, kept until the link text is written
+struct LinkDestination {
+    link_type: LinkType,
+    dest_url: String,
+    title: String,
+
+    /// Reference label, for reference links
+    id: String,
+    text_start: usize,
+}
+
+/// Link reference definition, written at the end of the document
+pub(super) struct ReferenceDefinition {
+    pub(super) label: String,
+    pub(super) dest_url: String,
+    pub(super) title: String,
+}
+
+/// `text` with Markdown punctuation escaped, so it is read back as plain text
+fn escaped_text(text: &str, in_table: bool) -> String {
+    let characters: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    for (index, character) in characters.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| characters[index]);
+        let next = characters.get(index + 1).copied();
+        let escape = match character {
+            '\\' | '`' | '*' | '[' | ']' | '~' => true,
+            // intraword underscores cannot open or close emphasis
+            '_' => {
+                !(previous.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric))
+            }
+            '<' => next.is_some_and(|c| c.is_ascii_alphabetic() || "/!?".contains(c)),
+            '&' => {
+                let rest: String = characters[index + 1..].iter().take(33).collect();
+                rest.starts_with('#')
+                    || rest.split_once(';').is_some_and(|(name, _)| {
+                        !name.is_empty() && name.chars().all(char::is_alphanumeric)
+                    })
+            }
+            '|' => in_table,
+            _ => false,
+        };
+        if escape {
+            result.push('\\');
+        }
+        result.push(*character);
+    }
+    result
+}
+
+/// `line` with a backslash added where its opening characters would otherwise start a heading,
+/// blockquote, list item or thematic break
+fn escaped_line_start(line: &str) -> String {
+    let followed_by_space = |rest: &str| rest.is_empty() || rest.starts_with([' ', '\t']);
+    let trimmed = line.trim_end();
+    if let Some(rest) = line.strip_prefix('#') {
+        if followed_by_space(rest.trim_start_matches('#')) {
+            return format!("\\{line}");
+        }
+    }
+    if line.starts_with('>')
+        || (line.starts_with(['-', '+']) && followed_by_space(&line[1..]))
+        || (trimmed.starts_with(['-', '=', '_'])
+            && trimmed
+                .chars()
+                .all(|c| c == trimmed.as_bytes()[0] as char || c == ' '))
+    {
+        return format!("\\{line}");
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if (1..10).contains(&digits)
+        && line[digits..].starts_with(['.', ')'])
+        && followed_by_space(&line[digits + 1..])
+    {
+        return format!("{}\\{}", &line[..digits], &line[digits..]);
+    }
+    line.to_string()
+}
+
+/// Inline code span, with a backtick fence longer than any run of backticks in `code`
+fn code_span(code: &str) -> String {
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    let padding = if code.starts_with('`')
+        || code.ends_with('`')
+        || (code.starts_with(' ') && code.ends_with(' ') && !code.trim().is_empty())
+    {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{padding}{code}{padding}{fence}")
+}
+
+/// `true` if every parenthesis in `url` is closed, in order
+fn balanced_parentheses(url: &str) -> bool {
+    let mut depth = 0_usize;
+    for character in url.chars() {
+        match character {
+            '(' => depth += 1,
+            ')' => match depth.checked_sub(1) {
+                Some(value) => depth = value,
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+/// Link destination, in angle brackets where it contains spaces, angle brackets or unbalanced
+/// parentheses
+fn link_destination(url: &str) -> String {
+    if url.is_empty() || url.contains([' ', '<', '>']) || !balanced_parentheses(url) {
+        let url = url
+            .replace('<', "%3C")
+            .replace('>', "%3E")
+            .replace(' ', &UNBREAKABLE_SPACE.to_string());
+        format!("<{url}>")
+    } else {
+        url.to_string()
+    }
+}
+
+/// Link destination followed by any title, as written between the parentheses of an inline link
+fn link_target(dest_url: &str, title: &str) -> String {
+    let destination = link_destination(dest_url);
+    if title.is_empty() {
+        destination
+    } else {
+        format!(
+            "{destination} \"{}\"",
+            title.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    }
+}
+
+fn heading_marker(level: HeadingLevel) -> &'static str {
+    match level {
+        HeadingLevel::H1 => "#",
+        HeadingLevel::H2 => "##",
+        HeadingLevel::H3 => "###",
+        HeadingLevel::H4 => "####",
+        HeadingLevel::H5 => "#####",
+        HeadingLevel::H6 => "######",
+    }
+}
+
+/// `{#id .class key=value}` heading attributes, or an empty string if there are none
+fn heading_attributes(
+    id: Option<&CowStr>,
+    classes: &[CowStr],
+    attrs: &[(CowStr, Option<CowStr>)],
+) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(id) = id {
+        parts.push(format!("#{id}"));
+    }
+    parts.extend(classes.iter().map(|class| format!(".{class}")));
+    parts.extend(attrs.iter().map(|(key, value)| match value {
+        Some(value) => format!("{key}={value}"),
+        None => key.to_string(),
+    }));
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" {{{}}}", parts.join(" "))
+    }
+}
+
+struct MarkdownWriter<'s, I> {
+    /// Iterator supplying events.
+    iter: I,
+
+    /// Output buffer
+    output: &'s mut String,
+
+    /// Whether a blank line is due before the next block
+    blank_line_needed: bool,
+
+    /// Blocks containing the current text, outermost first
+    containers: Vec<Container>,
+
+    /// Inline Markdown for the current block
+    current_line: String,
+
+    /// Open links and images
+    links: Vec<LinkDestination>,
+
+    /// Fence info string of the current code block, with its text kept in the current line
+    code_block: Option<String>,
+
+    /// Heading attributes, for headings with an id, classes or other attributes
+    heading_attributes: String,
+
+    /// Whether the last block closed was a list, so the next list uses different markers
+    list_ended: bool,
+
+    /// Whether the current text is a link written as `<url>`, and so is not escaped
+    in_autolink: bool,
+
+    table: Option<Table>,
+
+    /// Preferred maximum line length for paragraphs, or `None` to keep source line breaks
+    line_length: Option<usize>,
+
+    /// Link reference definitions from the source, written after the content
+    definitions: Vec<ReferenceDefinition>,
+
+    /// Lowercase labels of reference links, in the order they are first used
+    used_labels: Vec<String>,
+}
+
+impl<'a, 's, I> MarkdownWriter<'s, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    fn new(
+        iter: I,
+        output: &'s mut String,
+        line_length: Option<usize>,
+        definitions: Vec<ReferenceDefinition>,
+    ) -> Self {
+        Self {
+            iter,
+            output,
+            blank_line_needed: false,
+            containers: Vec::new(),
+            current_line: String::new(),
+            links: Vec::new(),
+            code_block: None,
+            heading_attributes: String::new(),
+            list_ended: false,
+            in_autolink: false,
+            table: None,
+            line_length,
+            definitions,
+            used_labels: Vec::new(),
+        }
+    }
+
+    /// Prefix for the next output line, from blockquote markers and list item markers or their
+    /// indent.  Item markers are only written once.
+    fn line_prefix(&mut self) -> String {
+        let mut result = String::new();
+        for container in &mut self.containers {
+            match container {
+                Container::BlockQuote => result.push_str("> "),
+                Container::List { .. } => {}
+                Container::Item {
+                    marker,
+                    marker_pending,
+                } => {
+                    if *marker_pending {
+                        result.push_str(marker);
+                        *marker_pending = false;
+                    } else {
+                        result.push_str(&" ".repeat(marker.len()));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Prefix for lines continuing the current block, with item markers replaced by their indent
+    fn continuation_prefix(&self) -> String {
+        self.containers
+            .iter()
+            .map(|container| match container {
+                Container::BlockQuote => String::from("> "),
+                Container::List { .. } => String::new(),
+                Container::Item { marker, .. } => " ".repeat(marker.len()),
+            })
+            .collect()
+    }
+
+    /// Writes a single line, with trailing spaces removed
+    fn write_line(&mut self, line: &str) {
+        self.output.push_str(line.trim_end_matches(' '));
+        self.output.push('\n');
+    }
+
+    /// Writes a blank line, if one is due, ahead of a new block
+    fn write_separator(&mut self) {
+        if self.blank_line_needed {
+            self.blank_line_needed = false;
+            if self.output.is_empty() {
+                return;
+            }
+            let prefix: String = self
+                .containers
+                .iter()
+                .map(|container| match container {
+                    Container::BlockQuote => ">",
+                    _ => "",
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.write_line(&prefix);
+        }
+    }
+
+    /// Writes the current line, split at hard breaks, which are saved as newlines, and wrapped to
+    /// any preferred line length
+    fn write(&mut self) {
+        let text = std::mem::take(&mut self.current_line);
+        let text = text.trim_matches(' ');
+        if text.is_empty() {
+            return;
+        }
+        for segment in text.split('\n') {
+            let segment = segment.trim_start_matches(' ');
+            let lines: Vec<String> = match self.line_length {
+                Some(line_length) => {
+                    // lines are only broken at spaces, never within code spans or URLs
+                    let width =
+                        line_length.saturating_sub(display_width(&self.continuation_prefix()));
+                    let options = Options::new(width.max(1))
+                        .word_separator(WordSeparator::AsciiSpace)
+                        .word_splitter(WordSplitter::NoHyphenation)
+                        .break_words(false);
+                    wrap(segment, options)
+                        .into_iter()
+                        .map(Cow::into_owned)
+                        .collect()
+                }
+                None => vec![segment.to_string()],
+            };
+            for line in lines {
+                let prefix = self.line_prefix();
+                let line = escaped_line_start(&line).replace(UNBREAKABLE_SPACE, " ");
+                self.write_line(&format!("{prefix}{line}"));
+            }
+        }
+    }
+
+    /// Writes any pending text, then a blank line if due, ahead of a new block
+    fn start_block(&mut self) {
+        self.write();
+        self.write_separator();
+        self.list_ended = false;
+    }
+
+    fn end_block(&mut self) {
+        self.write();
+        self.blank_line_needed = true;
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.code_block.is_some() || self.in_autolink {
+            self.current_line.push_str(text);
+        } else {
+            let escaped = escaped_text(text, self.table.is_some());
+            self.current_line.push_str(&escaped);
+        }
+    }
+
+    fn run(mut self) {
+        while let Some(event) = self.iter.next() {
+            match event {
+                Start(tag) => self.start_tag(tag),
+                End(tag) => self.end_tag(tag),
+                Text(text) => self.push_text(&text),
+                Code(code) => {
+                    let mut span = code_span(&code).replace(' ', &UNBREAKABLE_SPACE.to_string());
+                    // a bare pipe would end the table cell, even within a code span
+                    if self.table.is_some() {
+                        span = span.replace('|', "\\|");
+                    }
+                    self.current_line.push_str(&span);
+                }
+                InlineHtml(html) => {
+                    let html = html.replace(' ', &UNBREAKABLE_SPACE.to_string());
+                    self.current_line.push_str(&html);
+                }
+                Html(html) => {
+                    for line in html.lines() {
+                        let prefix = self.line_prefix();
+                        self.write_line(&format!("{prefix}{line}"));
+                    }
+                }
+                SoftBreak => {
+                    // source line breaks are kept unless paragraphs are rewrapped
+                    self.current_line.push(if self.line_length.is_some() {
+                        ' '
+                    } else {
+                        '\n'
+                    });
+                }
+                HardBreak => self.current_line.push_str("\\\n"),
+                Rule => {
+                    self.start_block();
+                    let prefix = self.line_prefix();
+                    self.write_line(&format!("{prefix}---"));
+                    self.blank_line_needed = true;
+                }
+                TaskListMarker(checked) => {
+                    self.current_line
+                        .push_str(if checked { "[x] " } else { "[ ] " });
+                }
+                _ => {}
+            }
+        }
+        self.write();
+        self.write_definitions();
+    }
+
+    fn start_tag(&mut self, tag: Tag<'a>) {
+        match tag {
+            Tag::Paragraph => {
+                if let [.., Container::List { loose, .. }, Container::Item { .. }] =
+                    &mut self.containers[..]
+                {
+                    *loose = true;
+                }
+                self.start_block();
+            }
+            Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            } => {
+                self.start_block();
+                self.heading_attributes = heading_attributes(id.as_ref(), &classes, &attrs);
+                self.current_line.push_str(heading_marker(level));
+                self.current_line.push(' ');
+            }
+            Tag::BlockQuote(_) => {
+                self.start_block();
+                self.containers.push(Container::BlockQuote);
+            }
+            Tag::CodeBlock(kind) => {
+                self.start_block();
+                self.code_block = Some(match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                });
+            }
+            Tag::HtmlBlock => self.start_block(),
+            Tag::List(start) => self.start_list(start),
+            Tag::Item => self.start_item(),
+            Tag::Table(alignments) => {
+                self.start_block();
+                self.table = Some(Table {
+                    alignments,
+                    rows: Vec::new(),
+                });
+            }
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(table) = &mut self.table {
+                    table.rows.push(Vec::new());
+                }
+            }
+            Tag::Emphasis => self.current_line.push('*'),
+            Tag::Strong => self.current_line.push_str("**"),
+            Tag::Strikethrough => self.current_line.push_str("~~"),
+            Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            } => {
+                let autolink = matches!(link_type, LinkType::Autolink | LinkType::Email);
+                self.in_autolink = autolink;
+                self.current_line.push(if autolink { '<' } else { '[' });
+                if matches!(link_type, LinkType::WikiLink { .. }) {
+                    self.current_line.push('[');
+                }
+                self.links.push(LinkDestination {
+                    link_type,
+                    dest_url: dest_url.to_string(),
+                    title: title.to_string(),
+                    id: id.to_string(),
+                    text_start: self.current_line.len(),
+                });
+            }
+            Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            } => {
+                self.current_line.push_str("![");
+                self.links.push(LinkDestination {
+                    link_type,
+                    dest_url: dest_url.to_string(),
+                    title: title.to_string(),
+                    id: id.to_string(),
+                    text_start: self.current_line.len(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn end_tag(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::HtmlBlock => self.end_block(),
+            TagEnd::Heading(_) => {
+                let attributes = std::mem::take(&mut self.heading_attributes);
+                // a closing `#` would otherwise be read as the end of the heading
+                if self.current_line.ends_with('#') {
+                    self.current_line.insert(self.current_line.len() - 1, '\\');
+                }
+                self.current_line.push_str(&attributes);
+                let prefix = self.line_prefix();
+                let line = std::mem::take(&mut self.current_line);
+                self.write_line(&format!(
+                    "{prefix}{}",
+                    line.replace(['\n', UNBREAKABLE_SPACE], " ")
+                ));
+                self.blank_line_needed = true;
+            }
+            TagEnd::BlockQuote(_) => {
+                self.write();
+                self.containers.pop();
+                self.blank_line_needed = true;
+            }
+            TagEnd::CodeBlock => self.end_code_block(),
+            TagEnd::List(_) => {
+                self.write();
+                self.containers.pop();
+                self.blank_line_needed = true;
+                self.list_ended = true;
+            }
+            TagEnd::Item => {
+                self.write();
+                if let Some(Container::Item {
+                    marker_pending: true,
+                    ..
+                }) = self.containers.last()
+                {
+                    let prefix = self.line_prefix();
+                    self.write_line(&prefix);
+                }
+                self.containers.pop();
+            }
+            TagEnd::TableCell => {
+                let cell = std::mem::take(&mut self.current_line);
+                if let Some(row) = self.table.as_mut().and_then(|table| table.rows.last_mut()) {
+                    row.push(cell.trim().replace(['\n', UNBREAKABLE_SPACE], " "));
+                }
+            }
+            TagEnd::Table => self.end_table(),
+            TagEnd::Emphasis => self.current_line.push('*'),
+            TagEnd::Strong => self.current_line.push_str("**"),
+            TagEnd::Strikethrough => self.current_line.push_str("~~"),
+            TagEnd::Link | TagEnd::Image => self.end_link(),
+            _ => {}
+        }
+    }
+
+    fn start_list(&mut self, start: Option<u64>) {
+        let alternate = self.list_ended;
+        // only lists starting at 1 can interrupt the text of a tight item
+        if start.is_some_and(|number| number != 1) && !self.current_line.trim().is_empty() {
+            self.blank_line_needed = true;
+        }
+        self.start_block();
+        let delimiter = match (start.is_some(), alternate) {
+            (false, false) => '-',
+            (false, true) => '*',
+            (true, false) => '.',
+            (true, true) => ')',
+        };
+        self.containers.push(Container::List {
+            next_number: start,
+            delimiter,
+            loose: false,
+            started: false,
+        });
+    }
+
+    fn start_item(&mut self) {
+        self.write();
+        let marker = match self.containers.last_mut() {
+            Some(Container::List {
+                next_number,
+                delimiter,
+                loose,
+                started,
+            }) => {
+                if *started {
+                    self.blank_line_needed = *loose;
+                }
+                *started = true;
+                match next_number {
+                    Some(number) => {
+                        *number += 1;
+                        format!("{}{delimiter} ", *number - 1)
+                    }
+                    None => format!("{delimiter} "),
+                }
+            }
+            _ => String::from("- "),
+        };
+        self.write_separator();
+        self.containers.push(Container::Item {
+            marker,
+            marker_pending: true,
+        });
+    }
+
+    fn end_link(&mut self) {
+        let Some(LinkDestination {
+            link_type,
+            dest_url,
+            title,
+            id,
+            text_start,
+        }) = self.links.pop()
+        else {
+            return;
+        };
+        match link_type {
+            LinkType::Autolink | LinkType::Email => {
+                self.in_autolink = false;
+                let text = &self.current_line[text_start..];
+                if text == dest_url || format!("mailto:{text}") == dest_url {
+                    self.current_line.push('>');
+                } else {
+                    // written as an inline link where the text differs from the address
+                    self.current_line
+                        .replace_range(text_start - 1..text_start, "[");
+                    let text = escaped_text(&self.current_line[text_start..], false);
+                    self.current_line.truncate(text_start);
+                    self.current_line.push_str(&text);
+                    self.current_line.push_str("](");
+                    self.current_line.push_str(&link_target(&dest_url, &title));
+                    self.current_line.push(')');
+                }
+            }
+            LinkType::WikiLink { has_pothole } => {
+                let text = &self.current_line[text_start..];
+                if has_pothole || text != dest_url {
+                    let text = text.to_string();
+                    self.current_line.truncate(text_start);
+                    self.current_line.push_str(&dest_url);
+                    self.current_line.push('|');
+                    self.current_line.push_str(&text);
+                }
+                self.current_line.push_str("]]");
+            }
+            LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut
+                if self.has_definition(&id) =>
+            {
+                let label = id.to_lowercase();
+                if !self.used_labels.contains(&label) {
+                    self.used_labels.push(label);
+                }
+                match link_type {
+                    LinkType::Reference => {
+                        self.current_line.push_str("][");
+                        self.current_line.push_str(&id);
+                        self.current_line.push(']');
+                    }
+                    LinkType::Collapsed => self.current_line.push_str("][]"),
+                    _ => self.current_line.push(']'),
+                }
+            }
+            _ => {
+                self.current_line.push_str("](");
+                self.current_line.push_str(&link_target(&dest_url, &title));
+                self.current_line.push(')');
+            }
+        }
+    }
+
+    fn has_definition(&self, label: &str) -> bool {
+        let label = label.to_lowercase();
+        self.definitions
+            .iter()
+            .any(|definition| definition.label.to_lowercase() == label)
+    }
+
+    /// Writes link reference definitions, in order of first use, followed by any unused ones
+    fn write_definitions(&mut self) {
+        if self.definitions.is_empty() {
+            return;
+        }
+        let mut definitions = std::mem::take(&mut self.definitions);
+        definitions.sort_by_key(|definition| {
+            let label = definition.label.to_lowercase();
+            self.used_labels
+                .iter()
+                .position(|used_label| *used_label == label)
+                .unwrap_or(usize::MAX)
+        });
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        for ReferenceDefinition {
+            label,
+            dest_url,
+            title,
+        } in definitions
+        {
+            let target = link_target(&dest_url, &title).replace(UNBREAKABLE_SPACE, " ");
+            self.write_line(&format!("[{label}]: {target}"));
+        }
+    }
+
+    fn end_code_block(&mut self) {
+        let code = std::mem::take(&mut self.current_line);
+        let info = self.code_block.take().unwrap_or_default();
+        let longest_run = code
+            .lines()
+            .map(|line| line.trim_start().chars().take_while(|c| *c == '`').count())
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+        let prefix = self.line_prefix();
+        self.write_line(&format!("{prefix}{fence}{info}"));
+        for line in code.strip_suffix('\n').unwrap_or(&code).lines() {
+            let prefix = self.line_prefix();
+            // lines are written in full, so trailing whitespace in code is kept
+            self.output.push_str(&prefix);
+            self.output.push_str(line);
+            self.output.push('\n');
+        }
+        let prefix = self.line_prefix();
+        self.write_line(&format!("{prefix}{fence}"));
+        self.blank_line_needed = true;
+    }
+
+    /// Writes the table with columns padded to a common width
+    fn end_table(&mut self) {
+        let Some(Table { alignments, rows }) = self.table.take() else {
+            return;
+        };
+        let columns = alignments.len();
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+        let format_row = |row: &Vec<String>| -> String {
+            let cells: Vec<String> = (0..columns)
+                .map(|column| {
+                    let cell = row.get(column).map_or("", String::as_str);
+                    let padding = widths[column].saturating_sub(display_width(cell));
+                    match alignments[column] {
+                        Alignment::Right => format!("{}{cell}", " ".repeat(padding)),
+                        Alignment::Center => format!(
+                            "{}{cell}{}",
+                            " ".repeat(padding / 2),
+                            " ".repeat(padding - padding / 2)
+                        ),
+                        Alignment::None | Alignment::Left => {
+                            format!("{cell}{}", " ".repeat(padding))
+                        }
+                    }
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let delimiter_row: Vec<String> = alignments
+            .iter()
+            .zip(&widths)
+            .map(|(alignment, width)| match alignment {
+                Alignment::None => "-".repeat(*width),
+                Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+            })
+            .collect();
+        let mut lines = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            lines.push(format_row(row));
+            if index == 0 {
+                lines.push(format!("| {} |", delimiter_row.join(" | ")));
+            }
+        }
+        for line in lines {
+            let prefix = self.line_prefix();
+            self.write_line(&format!("{prefix}{line}"));
+        }
+        self.blank_line_needed = true;
+    }
+}
+
+/// Writes Markdown for `iter` events to `s`, with paragraphs wrapped to `line_length`, if set,
+/// and `definitions` collected at the end
+pub(super) fn push_markdown<'a, I>(
+    s: &mut String,
+    iter: I,
+    line_length: Option<usize>,
+    definitions: Vec<ReferenceDefinition>,
+) where
+    I: Iterator<Item = Event<'a>>,
+{
+    MarkdownWriter::new(iter, s, line_length, definitions).run();
+}
+
+/// Writes Markdown for an HTML fragment to `s`
+pub(super) fn push_html_markdown(s: &mut String, html: &str) {
+    push_markdown(s, html_events(html).into_iter(), None, Vec::new());
+}
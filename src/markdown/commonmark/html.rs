@@ -0,0 +1,614 @@
+use html5ever::{
+    serialize::{SerializeOpts, TraversalScope, serialize},
+    tendril::TendrilSink,
+};
+use pulldown_cmark::{
+    Alignment, CodeBlockKind, CowStr, Event, HeadingLevel, LinkType, Tag, TagEnd,
+};
+
+use crate::{
+    html_process::{
+        Builder,
+        dom::{Handle, NodeData, SerializableHandle},
+    },
+    markdown::slugified_title,
+};
+
+/// Value of the `name` attribute of element `node`
+fn attribute(node: &Handle, name: &str) -> Option<String> {
+    let NodeData::Element { ref attrs, .. } = node.data else {
+        return None;
+    };
+    attrs
+        .borrow()
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| attr.value.to_string())
+}
+
+fn element_name(node: &Handle) -> Option<String> {
+    match node.data {
+        NodeData::Element { ref name, .. } => Some(name.local.to_string()),
+        _ => None,
+    }
+}
+
+/// Text of `node` and its descendants, with whitespace kept
+fn text_content(node: &Handle) -> String {
+    match node.data {
+        NodeData::Text { ref contents } => contents.borrow().to_string(),
+        NodeData::Element { .. } => node.children.borrow().iter().map(text_content).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Markup for `node`, including the element itself
+fn outer_html(node: &Handle) -> String {
+    let mut bytes = Vec::new();
+    let opts = SerializeOpts {
+        traversal_scope: TraversalScope::IncludeNode,
+        ..Default::default()
+    };
+    let handle = SerializableHandle::from(node.clone());
+    serialize(&mut bytes, &handle, opts)
+        .expect("Writing to a string shouldn't fail (expect on OOM)");
+    String::from_utf8(bytes).expect("html5ever only supports UTF8")
+}
+
+/// Opening tag for element `node`, with its attributes
+fn opening_tag(node: &Handle) -> String {
+    let NodeData::Element {
+        ref name,
+        ref attrs,
+        ..
+    } = node.data
+    else {
+        return String::new();
+    };
+    let mut result = format!("<{}", name.local);
+    for attr in attrs.borrow().iter() {
+        let value = attr.value.replace('&', "&amp;").replace('"', "&quot;");
+        result.push(' ');
+        result.push_str(&attr.name.local);
+        result.push_str("=\"");
+        result.push_str(&value);
+        result.push('"');
+    }
+    result.push('>');
+    result
+}
+
+fn heading_level(name: &str) -> Option<HeadingLevel> {
+    match name {
+        "h1" => Some(HeadingLevel::H1),
+        "h2" => Some(HeadingLevel::H2),
+        "h3" => Some(HeadingLevel::H3),
+        "h4" => Some(HeadingLevel::H4),
+        "h5" => Some(HeadingLevel::H5),
+        "h6" => Some(HeadingLevel::H6),
+        _ => None,
+    }
+}
+
+/// `true` for elements laid out as blocks, which end any paragraph of inline content
+fn is_block(name: &str) -> bool {
+    heading_level(name).is_some()
+        || matches!(
+            name,
+            "address"
+                | "article"
+                | "aside"
+                | "blockquote"
+                | "details"
+                | "dialog"
+                | "div"
+                | "dl"
+                | "fieldset"
+                | "figure"
+                | "footer"
+                | "form"
+                | "header"
+                | "hgroup"
+                | "hr"
+                | "iframe"
+                | "li"
+                | "main"
+                | "nav"
+                | "ol"
+                | "p"
+                | "pre"
+                | "section"
+                | "table"
+                | "ul"
+        )
+}
+
+/// `true` for elements with no Markdown counterpart, whose content is not converted
+fn is_raw(name: &str) -> bool {
+    matches!(
+        name,
+        "audio"
+            | "canvas"
+            | "iframe"
+            | "math"
+            | "object"
+            | "script"
+            | "style"
+            | "svg"
+            | "template"
+            | "textarea"
+            | "video"
+    )
+}
+
+fn is_void(name: &str) -> bool {
+    matches!(
+        name,
+        "area" | "base" | "col" | "embed" | "input" | "link" | "meta" | "source" | "track" | "wbr"
+    )
+}
+
+/// Alignment of a table cell, from its `align` attribute or `text-align` style
+fn cell_alignment(node: &Handle) -> Alignment {
+    let style = attribute(node, "style")
+        .unwrap_or_default()
+        .replace(' ', "");
+    let align = attribute(node, "align").or_else(|| {
+        style
+            .split(';')
+            .find_map(|declaration| declaration.strip_prefix("text-align:"))
+            .map(str::to_string)
+    });
+    match align.as_deref() {
+        Some("left") => Alignment::Left,
+        Some("center") => Alignment::Center,
+        Some("right") => Alignment::Right,
+        _ => Alignment::None,
+    }
+}
+
+/// Rows of a table, each a list of cells, or `None` if the table cannot be written as a GFM table
+fn table_rows(table: &Handle) -> Option<Vec<Vec<Handle>>> {
+    let mut rows = Vec::new();
+    let mut sections = vec![table.clone()];
+    while let Some(section) = sections.pop() {
+        for child in section.children.borrow().iter() {
+            match element_name(child).as_deref() {
+                Some("thead" | "tbody" | "tfoot") => sections.insert(0, child.clone()),
+                Some("tr") => {
+                    let cells: Vec<Handle> = child
+                        .children
+                        .borrow()
+                        .iter()
+                        .filter(|cell| matches!(element_name(cell).as_deref(), Some("td" | "th")))
+                        .cloned()
+                        .collect();
+                    let spanned = cells.iter().any(|cell| {
+                        attribute(cell, "colspan").is_some_and(|value| value.trim() != "1")
+                            || attribute(cell, "rowspan").is_some_and(|value| value.trim() != "1")
+                    });
+                    let nested = cells.iter().any(contains_block);
+                    if spanned || nested {
+                        return None;
+                    }
+                    rows.push(cells);
+                }
+                Some("caption") => return None,
+                _ => {}
+            }
+        }
+    }
+    (!rows.is_empty()).then_some(rows)
+}
+
+/// `true` if any descendant of `node` is a block, other than a paragraph
+fn contains_block(node: &Handle) -> bool {
+    node.children.borrow().iter().any(|child| {
+        element_name(child)
+            .is_some_and(|name| (is_block(&name) && name != "p") || contains_block(child))
+    })
+}
+
+/// Builds Markdown events for an HTML DOM
+struct EventBuilder {
+    events: Vec<Event<'static>>,
+}
+
+impl EventBuilder {
+    fn push_start(&mut self, tag: Tag<'static>) {
+        self.events.push(Event::Start(tag));
+    }
+
+    fn push_end(&mut self, tag: TagEnd) {
+        self.events.push(Event::End(tag));
+    }
+
+    /// Adds events for flow content, wrapping runs of inline content in paragraphs, unless
+    /// `bare_inline` is set, as for items of tight lists
+    fn flow_children(&mut self, node: &Handle, bare_inline: bool) {
+        let mut run: Vec<Handle> = Vec::new();
+        for child in node.children.borrow().iter() {
+            let block = element_name(child).is_some_and(|name| is_block(&name));
+            if block {
+                self.inline_run(&run, bare_inline);
+                run.clear();
+                self.block_node(child);
+            } else {
+                run.push(child.clone());
+            }
+        }
+        self.inline_run(&run, bare_inline);
+    }
+
+    /// Adds events for a run of inline nodes, with surrounding whitespace removed, as a paragraph
+    /// unless `bare` is set
+    fn inline_run(&mut self, nodes: &[Handle], bare: bool) {
+        let mut inline = EventBuilder { events: Vec::new() };
+        for node in nodes {
+            inline.inline_node(node);
+        }
+        let mut events = inline.events;
+        trim_inline_events(&mut events);
+        if events.is_empty() {
+            return;
+        }
+        if bare {
+            self.events.extend(events);
+        } else {
+            self.push_start(Tag::Paragraph);
+            self.events.extend(events);
+            self.push_end(TagEnd::Paragraph);
+        }
+    }
+
+    fn inline_children(&mut self, node: &Handle) {
+        for child in node.children.borrow().iter() {
+            self.inline_node(child);
+        }
+    }
+
+    /// Adds `html` as a raw HTML block
+    fn html_block(&mut self, html: &str) {
+        // a blank line would end the HTML block early
+        let lines: Vec<&str> = html
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+        self.push_start(Tag::HtmlBlock);
+        self.events
+            .push(Event::Html(CowStr::from(format!("{}\n", lines.join("\n")))));
+        self.push_end(TagEnd::HtmlBlock);
+    }
+
+    fn heading(&mut self, node: &Handle, level: HeadingLevel) {
+        let mut content = EventBuilder { events: Vec::new() };
+        content.inline_children(node);
+        let mut events = content.events;
+        trim_inline_events(&mut events);
+        let text: String = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Text(text) | Event::Code(text) => Some(text.to_string()),
+                _ => None,
+            })
+            .collect();
+        // ids matching the generated ones are left implicit
+        let id = attribute(node, "id")
+            .filter(|id| !id.is_empty() && *id != slugified_title(&text))
+            .map(CowStr::from);
+        self.push_start(Tag::Heading {
+            level,
+            id,
+            classes: Vec::new(),
+            attrs: Vec::new(),
+        });
+        self.events.extend(
+            events
+                .into_iter()
+                .filter(|event| !matches!(event, Event::HardBreak)),
+        );
+        self.push_end(TagEnd::Heading(level));
+    }
+
+    fn list(&mut self, node: &Handle, ordered: bool) {
+        let start = ordered.then(|| {
+            attribute(node, "start")
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(1)
+        });
+        self.push_start(Tag::List(start));
+        for child in node.children.borrow().iter() {
+            if element_name(child).as_deref() == Some("li") {
+                self.push_start(Tag::Item);
+                let loose = child
+                    .children
+                    .borrow()
+                    .iter()
+                    .any(|item_child| element_name(item_child).as_deref() == Some("p"));
+                self.flow_children(child, !loose);
+                self.push_end(TagEnd::Item);
+            }
+        }
+        self.push_end(TagEnd::List(ordered));
+    }
+
+    fn code_block(&mut self, node: &Handle) {
+        let code = node
+            .children
+            .borrow()
+            .iter()
+            .find(|child| element_name(child).as_deref() == Some("code"))
+            .cloned();
+        let language = code
+            .as_ref()
+            .and_then(|code| attribute(code, "class"))
+            .and_then(|class| {
+                class
+                    .split_whitespace()
+                    .find_map(|name| name.strip_prefix("language-").map(str::to_string))
+            })
+            .unwrap_or_default();
+        let text = text_content(node);
+        let mut text = text.strip_prefix('\n').unwrap_or(&text).to_string();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        self.push_start(Tag::CodeBlock(CodeBlockKind::Fenced(CowStr::from(
+            language,
+        ))));
+        self.events.push(Event::Text(CowStr::from(text)));
+        self.push_end(TagEnd::CodeBlock);
+    }
+
+    fn table(&mut self, node: &Handle) {
+        let Some(rows) = table_rows(node) else {
+            self.html_block(&outer_html(node));
+            return;
+        };
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let alignments = (0..columns)
+            .map(|column| rows[0].get(column).map_or(Alignment::None, cell_alignment))
+            .collect();
+        self.push_start(Tag::Table(alignments));
+        for (index, row) in rows.iter().enumerate() {
+            self.push_start(if index == 0 {
+                Tag::TableHead
+            } else {
+                Tag::TableRow
+            });
+            for column in 0..columns {
+                self.push_start(Tag::TableCell);
+                if let Some(cell) = row.get(column) {
+                    let mut content = EventBuilder { events: Vec::new() };
+                    content.inline_children(cell);
+                    let mut events = content.events;
+                    trim_inline_events(&mut events);
+                    self.events.extend(
+                        events
+                            .into_iter()
+                            .filter(|event| !matches!(event, Event::HardBreak)),
+                    );
+                }
+                self.push_end(TagEnd::TableCell);
+            }
+            self.push_end(if index == 0 {
+                TagEnd::TableHead
+            } else {
+                TagEnd::TableRow
+            });
+        }
+        self.push_end(TagEnd::Table);
+    }
+
+    fn block_node(&mut self, node: &Handle) {
+        let Some(name) = element_name(node) else {
+            return;
+        };
+        if let Some(level) = heading_level(&name) {
+            self.heading(node, level);
+            return;
+        }
+        match name.as_str() {
+            "p" => self.inline_run(&node.children.borrow(), false),
+            "hr" => self.events.push(Event::Rule),
+            "blockquote" => {
+                self.push_start(Tag::BlockQuote(None));
+                self.flow_children(node, false);
+                self.push_end(TagEnd::BlockQuote(None));
+            }
+            "ol" | "ul" => self.list(node, name == "ol"),
+            "pre" => self.code_block(node),
+            "table" => self.table(node),
+            "article" | "aside" | "div" | "footer" | "header" | "hgroup" | "main" | "nav"
+            | "section" => {
+                if attribute(node, "class").is_none() && attribute(node, "id").is_none() {
+                    self.flow_children(node, false);
+                } else {
+                    self.html_block(&outer_html(node));
+                }
+            }
+            "li" => self.flow_children(node, false),
+            _ => self.html_block(&outer_html(node)),
+        }
+    }
+
+    fn inline_node(&mut self, node: &Handle) {
+        let name = match node.data {
+            NodeData::Text { ref contents } => {
+                self.events
+                    .push(Event::Text(CowStr::from(collapsed_whitespace(
+                        &contents.borrow(),
+                    ))));
+                return;
+            }
+            NodeData::Element { ref name, .. } => name.local.to_string(),
+            _ => return,
+        };
+        match name.as_str() {
+            "br" => self.events.push(Event::HardBreak),
+            "code" => {
+                let code = collapsed_whitespace(&text_content(node));
+                self.events.push(Event::Code(CowStr::from(code)));
+            }
+            "em" | "i" => self.wrapped_inline(node, Tag::Emphasis, TagEnd::Emphasis),
+            "b" | "strong" => self.wrapped_inline(node, Tag::Strong, TagEnd::Strong),
+            "del" | "s" | "strike" => {
+                self.wrapped_inline(node, Tag::Strikethrough, TagEnd::Strikethrough);
+            }
+            "a" => match attribute(node, "href") {
+                // anchors added to headings are generated again from the Markdown
+                _ if attribute(node, "class")
+                    .is_some_and(|class| class.contains("heading-anchor")) => {}
+                Some(href) => self.wrapped_inline(
+                    node,
+                    Tag::Link {
+                        link_type: LinkType::Inline,
+                        dest_url: CowStr::from(href),
+                        title: CowStr::from(attribute(node, "title").unwrap_or_default()),
+                        id: CowStr::Borrowed(""),
+                    },
+                    TagEnd::Link,
+                ),
+                None => self.inline_children(node),
+            },
+            "img" => {
+                self.push_start(Tag::Image {
+                    link_type: LinkType::Inline,
+                    dest_url: CowStr::from(attribute(node, "src").unwrap_or_default()),
+                    title: CowStr::from(attribute(node, "title").unwrap_or_default()),
+                    id: CowStr::Borrowed(""),
+                });
+                let alt = attribute(node, "alt").unwrap_or_default();
+                if !alt.is_empty() {
+                    self.events.push(Event::Text(CowStr::from(alt)));
+                }
+                self.push_end(TagEnd::Image);
+            }
+            "span" if attribute(node, "class").is_none() && attribute(node, "style").is_none() => {
+                self.inline_children(node);
+            }
+            _ if is_raw(&name) => {
+                self.events
+                    .push(Event::InlineHtml(CowStr::from(outer_html(node))));
+            }
+            _ if is_void(&name) => {
+                self.events
+                    .push(Event::InlineHtml(CowStr::from(opening_tag(node))));
+            }
+            _ => {
+                // unknown elements are kept as inline HTML around their converted content
+                self.events
+                    .push(Event::InlineHtml(CowStr::from(opening_tag(node))));
+                self.inline_children(node);
+                self.events
+                    .push(Event::InlineHtml(CowStr::from(format!("</{name}>"))));
+            }
+        }
+    }
+
+    fn wrapped_inline(&mut self, node: &Handle, start: Tag<'static>, end: TagEnd) {
+        self.push_start(start);
+        self.inline_children(node);
+        self.push_end(end);
+    }
+}
+
+/// `text` with runs of HTML whitespace replaced by single spaces
+fn collapsed_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous_space = false;
+    for character in text.chars() {
+        if character.is_ascii_whitespace() {
+            if !previous_space {
+                result.push(' ');
+            }
+            previous_space = true;
+        } else {
+            result.push(character);
+            previous_space = false;
+        }
+    }
+    result
+}
+
+/// Removes whitespace at the start and end of inline content, and between adjacent text, along
+/// with hard breaks at the end, which Markdown cannot express
+fn trim_inline_events(events: &mut Vec<Event<'static>>) {
+    let mut at_start = true;
+    let mut after_space = false;
+    for event in events.iter_mut() {
+        match event {
+            Event::Text(text) => {
+                let mut value = text.to_string();
+                if at_start || after_space {
+                    value = value.trim_start_matches(' ').to_string();
+                }
+                if !value.is_empty() {
+                    at_start = false;
+                    after_space = value.ends_with(' ');
+                }
+                *text = CowStr::from(value);
+            }
+            Event::HardBreak => after_space = true,
+            Event::Code(_) | Event::InlineHtml(_) | Event::End(TagEnd::Image) => {
+                at_start = false;
+                after_space = false;
+            }
+            _ => {}
+        }
+    }
+    events.retain(|event| !matches!(event, Event::Text(text) if text.is_empty()));
+    loop {
+        let last_text = events
+            .iter()
+            .rposition(|event| !matches!(event, Event::End(_) | Event::Start(_)));
+        match last_text.map(|index| (index, &mut events[index])) {
+            Some((index, Event::HardBreak)) => {
+                events.remove(index);
+            }
+            Some((index, Event::Text(text))) if text.ends_with(' ') => {
+                let value = text.trim_end_matches(' ').to_string();
+                if value.is_empty() {
+                    events.remove(index);
+                } else {
+                    *text = CowStr::from(value);
+                    break;
+                }
+            }
+            _ => break,
+        }
+    }
+    // text before a hard break would otherwise end in a space
+    for index in 1..events.len() {
+        if matches!(events[index], Event::HardBreak) {
+            if let Event::Text(text) = &mut events[index - 1] {
+                *text = CowStr::from(text.trim_end_matches(' ').to_string());
+            }
+        }
+    }
+    if events.iter().all(|event| {
+        matches!(event, Event::Start(_) | Event::End(_))
+            && !matches!(event, Event::Start(Tag::Image { .. }))
+    }) {
+        events.clear();
+    }
+}
+
+/// Markdown events for an HTML fragment
+pub(super) fn html_events(html: &str) -> Vec<Event<'static>> {
+    let dom = Builder::make_parser().one(html);
+    let root = dom.document.children.borrow()[0].clone();
+    let body = root
+        .children
+        .borrow()
+        .iter()
+        .find(|child| element_name(child).as_deref() == Some("body"))
+        .cloned();
+    let body = body.unwrap_or(root);
+    let mut builder = EventBuilder { events: Vec::new() };
+    builder.flow_children(&body, false);
+    builder.events
+}
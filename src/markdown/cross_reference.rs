@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+use pulldown_cmark_escape::{escape_href, escape_html};
+use serde::Serialize;
+
+use super::slugified_title;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossReferenceKind {
+    WikiLink,
+    Mention,
+    Hashtag,
+}
+
+impl CrossReferenceKind {
+    fn class(self) -> &'static str {
+        match self {
+            CrossReferenceKind::WikiLink => "wiki-link",
+            CrossReferenceKind::Mention => "mention",
+            CrossReferenceKind::Hashtag => "hashtag",
+        }
+    }
+}
+
+/// Wiki-link, mention or hashtag resolved to a URL, listed so callers can build backlinks
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CrossReference {
+    kind: CrossReferenceKind,
+
+    /// Page name, username or topic, as written
+    target: String,
+    href: String,
+}
+
+impl CrossReference {
+    pub fn new(kind: CrossReferenceKind, target: &str, href: &str) -> CrossReference {
+        CrossReference {
+            kind,
+            target: target.into(),
+            href: href.into(),
+        }
+    }
+}
+
+/// URL templates for wiki-links, mentions and hashtags.  `{slug}` in a template is replaced with
+/// the slugified page name or topic, or the username.  Syntax with no template is left as text.
+#[derive(Debug, Default)]
+pub struct CrossReferenceTemplates<'a> {
+    pub wiki_link: Option<&'a str>,
+    pub mention: Option<&'a str>,
+    pub hashtag: Option<&'a str>,
+}
+
+impl CrossReferenceTemplates<'_> {
+    fn template(&self, kind: CrossReferenceKind) -> Option<&str> {
+        match kind {
+            CrossReferenceKind::WikiLink => self.wiki_link,
+            CrossReferenceKind::Mention => self.mention,
+            CrossReferenceKind::Hashtag => self.hashtag,
+        }
+    }
+
+    fn resolve(&self, kind: CrossReferenceKind, target: &str) -> Option<String> {
+        let template = self.template(kind)?;
+        let href = match kind {
+            CrossReferenceKind::WikiLink => {
+                let (page, section) = match target.split_once('#') {
+                    Some((page, section)) => (page, Some(section)),
+                    None => (target, None),
+                };
+                let mut href = template.replace("{slug}", &slugified_title(page));
+                if let Some(value) = section {
+                    href.push('#');
+                    href.push_str(&slugified_title(value));
+                }
+                href
+            }
+            CrossReferenceKind::Mention => template.replace("{slug}", target),
+            CrossReferenceKind::Hashtag => template.replace("{slug}", &slugified_title(target)),
+        };
+        Some(href)
+    }
+}
+
+fn opening_anchor(href: &str, kind: CrossReferenceKind) -> String {
+    let mut result = String::from("<a href=\"");
+    escape_href(&mut result, href).expect("Writing to a string should not fail");
+    result.push_str("\" class=\"");
+    escape_html(&mut result, kind.class()).expect("Writing to a string should not fail");
+    result.push_str("\">");
+    result
+}
+
+/// Length, in bytes, of the mention or hashtag name at the start of `text`
+fn name_length(text: &str) -> usize {
+    let length = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '.'))
+        .unwrap_or(text.len());
+    text[..length].trim_end_matches(['.', '-']).len()
+}
+
+/// Adaptor over Markdown events, which turns wiki-links, `@username` mentions and `#topic`
+/// hashtags into class-styled anchors, collecting each resolved target
+pub struct CrossReferences<'a, 'b, I> {
+    iter: I,
+    templates: &'b CrossReferenceTemplates<'b>,
+    references: &'b mut Vec<CrossReference>,
+    pending: VecDeque<Event<'a>>,
+
+    /// Depth of links, images and code blocks, whose text is left unchanged
+    literal_depth: usize,
+    in_wiki_link: bool,
+}
+
+impl<'a, 'b, I> CrossReferences<'a, 'b, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    pub fn new(
+        iter: I,
+        templates: &'b CrossReferenceTemplates<'b>,
+        references: &'b mut Vec<CrossReference>,
+    ) -> Self {
+        Self {
+            iter,
+            templates,
+            references,
+            pending: VecDeque::new(),
+            literal_depth: 0,
+            in_wiki_link: false,
+        }
+    }
+
+    /// Splits `text` into text and anchor events for each mention or hashtag with a template
+    fn expand_text(&mut self, text: &str) {
+        let mut index = 0;
+        let mut previous: Option<char> = None;
+        for (position, c) in text.char_indices() {
+            let boundary = previous.is_none_or(|value| value.is_whitespace() || value == '(');
+            previous = Some(c);
+            if position < index || !boundary {
+                continue;
+            }
+            let kind = match c {
+                '@' => CrossReferenceKind::Mention,
+                '#' => CrossReferenceKind::Hashtag,
+                _ => continue,
+            };
+            let rest = &text[position + 1..];
+            let length = name_length(rest);
+            let name = &rest[..length];
+            if length == 0
+                || (kind == CrossReferenceKind::Hashtag && !name.contains(char::is_alphabetic))
+            {
+                continue;
+            }
+            let Some(href) = self.templates.resolve(kind, name) else {
+                continue;
+            };
+            if position > index {
+                self.pending
+                    .push_back(Event::Text(CowStr::from(text[index..position].to_string())));
+            }
+            self.pending
+                .push_back(Event::InlineHtml(opening_anchor(&href, kind).into()));
+            self.pending.push_back(Event::Text(CowStr::from(
+                text[position..position + 1 + length].to_string(),
+            )));
+            self.pending.push_back(Event::InlineHtml("</a>".into()));
+            self.references.push(CrossReference::new(kind, name, &href));
+            index = position + 1 + length;
+        }
+        if index < text.len() {
+            self.pending
+                .push_back(Event::Text(CowStr::from(text[index..].to_string())));
+        }
+    }
+}
+
+impl<'a, I> Iterator for CrossReferences<'a, '_, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        let event = self.iter.next()?;
+        match event {
+            Event::Start(Tag::Link {
+                link_type: LinkType::WikiLink { .. },
+                ref dest_url,
+                ..
+            }) => {
+                let target = dest_url.to_string();
+                if let Some(href) = self
+                    .templates
+                    .resolve(CrossReferenceKind::WikiLink, &target)
+                {
+                    self.in_wiki_link = true;
+                    self.references.push(CrossReference::new(
+                        CrossReferenceKind::WikiLink,
+                        &target,
+                        &href,
+                    ));
+                    Some(Event::InlineHtml(
+                        opening_anchor(&href, CrossReferenceKind::WikiLink).into(),
+                    ))
+                } else {
+                    self.literal_depth += 1;
+                    Some(event)
+                }
+            }
+            Event::End(TagEnd::Link) if self.in_wiki_link => {
+                self.in_wiki_link = false;
+                Some(Event::InlineHtml("</a>".into()))
+            }
+            Event::Start(Tag::Link { .. } | Tag::Image { .. } | Tag::CodeBlock(_)) => {
+                self.literal_depth += 1;
+                Some(event)
+            }
+            Event::End(TagEnd::Link | TagEnd::Image | TagEnd::CodeBlock) => {
+                self.literal_depth = self.literal_depth.saturating_sub(1);
+                Some(event)
+            }
+            Event::Text(ref text)
+                if self.literal_depth == 0
+                    && !self.in_wiki_link
+                    && (self.templates.mention.is_some() || self.templates.hashtag.is_some()) =>
+            {
+                let text = text.to_string();
+                self.expand_text(&text);
+                self.pending.pop_front().or_else(|| self.next())
+            }
+            _ => Some(event),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::iter::Peekable;
+
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+
+use crate::text::expand_shortcodes;
+
+/// Adaptor over Markdown events replacing `:shortcode:` emoji in text outside code.  When
+/// disabled, events pass through unchanged.
+pub struct EmojiShortcodes<I: Iterator> {
+    iter: Peekable<I>,
+    enabled: bool,
+    in_code_block: bool,
+}
+
+impl<'a, I> EmojiShortcodes<I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    pub fn new(iter: I, enabled: bool) -> Self {
+        Self {
+            iter: iter.peekable(),
+            enabled,
+            in_code_block: false,
+        }
+    }
+}
+
+impl<'a, I> Iterator for EmojiShortcodes<I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.iter.next()?;
+        if !self.enabled {
+            return Some(event);
+        }
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.in_code_block = true;
+                Some(event)
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                self.in_code_block = false;
+                Some(event)
+            }
+            Event::Text(text) if !self.in_code_block => {
+                // the parser may split text at `:`, so adjacent text is joined before expanding
+                let mut text = text;
+                while let Some(Event::Text(next)) = self.iter.peek() {
+                    text = CowStr::from(format!("{text}{next}"));
+                    self.iter.next();
+                }
+                let result = expand_shortcodes(&text).into_owned();
+                Some(Event::Text(CowStr::from(result)))
+            }
+            _ => Some(event),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use pulldown_cmark::{CowStr, Event, Options, Tag, TagEnd, html};
+use serde::Serialize;
+
+use super::{
+    ParseMarkdownOptions, cross_reference::CrossReferences, markdown_events, parser_options,
+};
+
+const ELLIPSIS: &str = "\u{2026}";
+
+/// Opening summary of a document, up to a `<!-- more -->` marker or a word limit, with headings,
+/// code blocks, images, tables and raw HTML left out
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct Excerpt {
+    html: String,
+    plaintext: String,
+
+    /// Single-line plaintext, shortened for `<meta name="description">`
+    description: String,
+}
+
+impl Excerpt {
+    #[allow(dead_code)]
+    pub fn new(html: &str, plaintext: &str, description: &str) -> Self {
+        Self {
+            html: html.into(),
+            plaintext: plaintext.into(),
+            description: description.into(),
+        }
+    }
+
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+
+    /// Replaces the excerpt HTML, after post-processing
+    pub fn set_html(&mut self, value: String) {
+        self.html = value;
+    }
+}
+
+/// `true` for a `<!-- more -->` marker, ignoring case and whitespace
+fn is_more_marker(html: &str) -> bool {
+    html.split_whitespace()
+        .collect::<String>()
+        .eq_ignore_ascii_case("<!--more-->")
+}
+
+/// `true` for tags whose content is left out of excerpts
+fn skipped_tag(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Heading { .. }
+            | Tag::CodeBlock(_)
+            | Tag::Image { .. }
+            | Tag::Table(_)
+            | Tag::FootnoteDefinition(_)
+            | Tag::HtmlBlock
+            | Tag::MetadataBlock(_)
+    )
+}
+
+fn skipped_tag_end(tag: TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Heading(_)
+            | TagEnd::CodeBlock
+            | TagEnd::Image
+            | TagEnd::Table
+            | TagEnd::FootnoteDefinition
+            | TagEnd::HtmlBlock
+            | TagEnd::MetadataBlock(_)
+    )
+}
+
+/// Excerpt events, and whether a `<!-- more -->` marker ended them
+fn excerpt_events<'a>(
+    markdown: &'a str,
+    markdown_options: &ParseMarkdownOptions,
+) -> (Vec<Event<'a>>, bool) {
+    let mut options = parser_options(markdown_options);
+    // locale typography replaces the parser's English smart punctuation
+    if markdown_options.locale.is_none() {
+        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
+    let mut result = Vec::new();
+    let mut skip_depth = 0;
+    for event in markdown_events(markdown, options, markdown_options) {
+        match event {
+            Event::Html(ref value) | Event::InlineHtml(ref value) if is_more_marker(value) => {
+                return (result, true);
+            }
+            Event::Start(ref tag) if skipped_tag(tag) => skip_depth += 1,
+            Event::End(tag) if skipped_tag_end(tag) => skip_depth -= 1,
+            Event::Html(_) | Event::InlineHtml(_) | Event::FootnoteReference(_) => {}
+            _ if skip_depth > 0 => {}
+            _ => result.push(event),
+        }
+    }
+    (result, false)
+}
+
+/// Plaintext contributed by `event`
+fn event_text<'b>(event: &'b Event) -> &'b str {
+    match event {
+        Event::Text(value) | Event::Code(value) => value,
+        Event::SoftBreak => " ",
+        Event::HardBreak | Event::End(TagEnd::Item) => "\n",
+        Event::End(TagEnd::Paragraph | TagEnd::BlockQuote(_)) => "\n\n",
+        _ => "",
+    }
+}
+
+/// Byte offset to cut `text` at, and whether an ellipsis is needed, for an excerpt of at most
+/// `max_words` words.  Ends at the last complete sentence within the limit, if that keeps at least
+/// half of the words.  Returns `None` if `text` is short enough already.
+fn truncation_offset(text: &str, max_words: usize) -> Option<(usize, bool)> {
+    let word_ends: Vec<(usize, &str)> = text
+        .split_whitespace()
+        .take(max_words + 1)
+        .map(|word| {
+            (
+                word.as_ptr() as usize - text.as_ptr() as usize + word.len(),
+                word,
+            )
+        })
+        .collect();
+    if word_ends.len() <= max_words || max_words == 0 {
+        return None;
+    }
+    let sentence_end = word_ends[..max_words]
+        .iter()
+        .rposition(|(_, word)| {
+            word.trim_end_matches(['"', '\'', ')', '\u{201d}', '\u{2019}', '\u{bb}'])
+                .ends_with(['.', '!', '?', '\u{2026}'])
+        })
+        .filter(|position| position + 1 >= max_words.div_ceil(2));
+    match sentence_end {
+        Some(position) => Some((word_ends[position].0, false)),
+        None => Some((word_ends[max_words - 1].0, true)),
+    }
+}
+
+/// `events` cut at plaintext byte `offset`, with open tags closed
+fn truncated_events(events: Vec<Event<'_>>, offset: usize, ellipsis: bool) -> Vec<Event<'_>> {
+    let mut result = Vec::new();
+    let mut open_tags = Vec::new();
+    let mut position = 0;
+    for event in events {
+        let text_length = event_text(&event).len();
+        if position + text_length > offset {
+            let kept = offset - position;
+            match event {
+                Event::Text(value) => {
+                    result.push(Event::Text(CowStr::from(value[..kept].to_string())));
+                }
+                Event::Code(value) => {
+                    result.push(Event::Code(CowStr::from(value[..kept].to_string())));
+                }
+                _ => {}
+            }
+            break;
+        }
+        position += text_length;
+        match event {
+            Event::Start(ref tag) => open_tags.push(tag.to_end()),
+            Event::End(_) => {
+                open_tags.pop();
+            }
+            _ => {}
+        }
+        result.push(event);
+        if position == offset {
+            break;
+        }
+    }
+    if ellipsis {
+        result.push(Event::Text(CowStr::from(ELLIPSIS)));
+    }
+    result.extend(open_tags.into_iter().rev().map(Event::End));
+    result
+}
+
+/// `text` on a single line, shortened at a word boundary to at most `max_length` characters
+fn description(text: &str, max_length: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= max_length {
+        return collapsed;
+    }
+    let mut result = String::new();
+    for word in collapsed.split(' ') {
+        let length =
+            result.chars().count() + usize::from(!result.is_empty()) + word.chars().count();
+        if length + 1 > max_length {
+            break;
+        }
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        result.push_str(word);
+    }
+    let mut result = result
+        .trim_end_matches([',', ';', ':', '.', '\u{2013}', '\u{2014}'])
+        .to_string();
+    result.push_str(ELLIPSIS);
+    result
+}
+
+/// Excerpt of `markdown`, ending at a `<!-- more -->` marker if there is one, and otherwise
+/// limited to the `excerpt_words` option
+pub fn markdown_excerpt(markdown: &str, markdown_options: &ParseMarkdownOptions) -> Excerpt {
+    let (events, marker_found) = excerpt_events(markdown, markdown_options);
+    let text: String = events.iter().map(event_text).collect();
+    let truncation = if marker_found {
+        None
+    } else {
+        truncation_offset(&text, markdown_options.excerpt_words)
+    };
+    let (events, plaintext) = match truncation {
+        Some((offset, ellipsis)) => {
+            let mut plaintext = text[..offset].trim_end().to_string();
+            if ellipsis {
+                plaintext.push_str(ELLIPSIS);
+            }
+            (truncated_events(events, offset, ellipsis), plaintext)
+        }
+        None => (events, text.trim_end().to_string()),
+    };
+    let mut html = String::new();
+    let mut cross_references = Vec::new();
+    html::push_html(
+        &mut html,
+        CrossReferences::new(
+            events.into_iter(),
+            &markdown_options.cross_reference_templates,
+            &mut cross_references,
+        ),
+    );
+    Excerpt {
+        description: description(&plaintext, markdown_options.description_length),
+        html,
+        plaintext,
+    }
+}
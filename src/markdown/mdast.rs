@@ -0,0 +1,557 @@
+use std::ops::Range;
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+use super::{
+    ParseMarkdownOptions, adjusted_heading_level, heading_id, markdown_events, parser_options,
+};
+
+/// Location in the source, with one-based line and column, and offset counted in UTF-16 code
+/// units, matching JavaScript string indices
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Point {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+#[cfg(test)]
+impl Point {
+    pub fn new(line: usize, column: usize, offset: usize) -> Self {
+        Self {
+            line,
+            column,
+            offset,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Position {
+    start: Point,
+    end: Point,
+}
+
+#[cfg(test)]
+impl Position {
+    pub fn new(start: Point, end: Point) -> Self {
+        Self { start, end }
+    }
+}
+
+/// Computed heading id, as `data.id` and `data.hProperties.id`, following remark-slug
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadingData {
+    id: String,
+    h_properties: HeadingProperties,
+}
+
+impl HeadingData {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.into(),
+            h_properties: HeadingProperties { id: id.into() },
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct HeadingProperties {
+    id: String,
+}
+
+/// Markdown syntax tree node, serialised in the unified mdast shape
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Node {
+    Root {
+        children: Vec<Node>,
+        position: Position,
+    },
+    Paragraph {
+        children: Vec<Node>,
+        position: Position,
+    },
+    Heading {
+        depth: u8,
+        children: Vec<Node>,
+        data: HeadingData,
+        position: Position,
+    },
+    ThematicBreak {
+        position: Position,
+    },
+    Blockquote {
+        children: Vec<Node>,
+        position: Position,
+    },
+    List {
+        ordered: bool,
+        start: Option<u64>,
+
+        /// Whether items are separated by blank lines
+        spread: bool,
+        children: Vec<Node>,
+        position: Position,
+    },
+    ListItem {
+        spread: bool,
+        checked: Option<bool>,
+        children: Vec<Node>,
+        position: Position,
+    },
+    Code {
+        lang: Option<String>,
+        meta: Option<String>,
+        value: String,
+        position: Position,
+    },
+    Html {
+        value: String,
+        position: Position,
+    },
+    Table {
+        /// `left`, `right`, `center` or `null` for each column
+        align: Vec<Option<&'static str>>,
+        children: Vec<Node>,
+        position: Position,
+    },
+    TableRow {
+        children: Vec<Node>,
+        position: Position,
+    },
+    TableCell {
+        children: Vec<Node>,
+        position: Position,
+    },
+    Text {
+        value: String,
+        position: Position,
+    },
+    Emphasis {
+        children: Vec<Node>,
+        position: Position,
+    },
+    Strong {
+        children: Vec<Node>,
+        position: Position,
+    },
+    Delete {
+        children: Vec<Node>,
+        position: Position,
+    },
+    InlineCode {
+        value: String,
+        position: Position,
+    },
+    Break {
+        position: Position,
+    },
+    Link {
+        url: String,
+        title: Option<String>,
+        children: Vec<Node>,
+        position: Position,
+    },
+    Image {
+        url: String,
+        title: Option<String>,
+        alt: String,
+        position: Position,
+    },
+}
+
+impl Node {
+    fn children_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Root { children, .. }
+            | Node::Paragraph { children, .. }
+            | Node::Heading { children, .. }
+            | Node::Blockquote { children, .. }
+            | Node::List { children, .. }
+            | Node::ListItem { children, .. }
+            | Node::Table { children, .. }
+            | Node::TableRow { children, .. }
+            | Node::TableCell { children, .. }
+            | Node::Emphasis { children, .. }
+            | Node::Strong { children, .. }
+            | Node::Delete { children, .. }
+            | Node::Link { children, .. } => Some(children),
+            _ => None,
+        }
+    }
+}
+
+/// Converts byte offsets in the source to line, column and UTF-16 offset
+struct Locator<'a> {
+    markdown: &'a str,
+
+    /// Byte offset and UTF-16 offset of the start of each line
+    line_starts: Vec<(usize, usize)>,
+}
+
+impl<'a> Locator<'a> {
+    fn new(markdown: &'a str) -> Self {
+        let mut line_starts = vec![(0, 0)];
+        let mut utf16_offset = 0;
+        for (index, character) in markdown.char_indices() {
+            utf16_offset += character.len_utf16();
+            if character == '\n' {
+                line_starts.push((index + 1, utf16_offset));
+            }
+        }
+        Self {
+            markdown,
+            line_starts,
+        }
+    }
+
+    fn point(&self, offset: usize) -> Point {
+        let line = self
+            .line_starts
+            .partition_point(|(line_start, _)| *line_start <= offset);
+        let (line_start, line_start_utf16) = self.line_starts[line - 1];
+        let column = self.markdown[line_start..offset].encode_utf16().count();
+        Point {
+            line,
+            column: column + 1,
+            offset: line_start_utf16 + column,
+        }
+    }
+
+    fn position(&self, range: &Range<usize>) -> Position {
+        Position {
+            start: self.point(range.start),
+            end: self.point(range.end),
+        }
+    }
+}
+
+/// Heading ids, in document order, as generated for the HTML output
+fn heading_ids(
+    markdown: &str,
+    options: Options,
+    markdown_options: &ParseMarkdownOptions,
+) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut fragments = String::new();
+    let mut custom_id: Option<String> = None;
+    let mut parsing_heading = false;
+    for event in markdown_events(markdown, options, markdown_options) {
+        match event {
+            Event::Start(Tag::Heading { id, .. }) => {
+                parsing_heading = true;
+                custom_id = id.as_ref().map(ToString::to_string);
+            }
+            Event::Text(value) | Event::Code(value) if parsing_heading => {
+                fragments.push_str(&value);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                result.push(heading_id(&fragments, custom_id.take(), markdown_options));
+                fragments.clear();
+                parsing_heading = false;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Language and any further meta string from a fenced code block info string
+fn code_info(kind: CodeBlockKind) -> (Option<String>, Option<String>) {
+    let CodeBlockKind::Fenced(info) = kind else {
+        return (None, None);
+    };
+    let info = info.trim();
+    match info.split_once(char::is_whitespace) {
+        Some((lang, meta)) => (Some(lang.to_string()), Some(meta.trim().to_string())),
+        None if info.is_empty() => (None, None),
+        None => (Some(info.to_string()), None),
+    }
+}
+
+fn alignment_name(alignment: Alignment) -> Option<&'static str> {
+    match alignment {
+        Alignment::None => None,
+        Alignment::Left => Some("left"),
+        Alignment::Center => Some("center"),
+        Alignment::Right => Some("right"),
+    }
+}
+
+/// Builds the tree from events, keeping nodes still open on a stack
+struct TreeBuilder<'a> {
+    locator: Locator<'a>,
+    stack: Vec<Node>,
+    heading_ids: std::vec::IntoIter<String>,
+    markdown_options: &'a ParseMarkdownOptions<'a>,
+
+    /// Depth of tags inside an image, whose text is collected as the image `alt`
+    image_depth: usize,
+}
+
+impl TreeBuilder<'_> {
+    fn push_child(&mut self, node: Node) {
+        let Some(children) = self.stack.last_mut().and_then(Node::children_mut) else {
+            return;
+        };
+        // adjacent text, split by the parser, and soft breaks are merged, as in mdast
+        if let (
+            Some(Node::Text {
+                value, position, ..
+            }),
+            Node::Text {
+                value: next_value,
+                position: next_position,
+            },
+        ) = (children.last_mut(), &node)
+        {
+            value.push_str(next_value);
+            position.end = next_position.end;
+            return;
+        }
+        children.push(node);
+    }
+
+    /// `true` while an image, code block or HTML block is open, and text is collected as its
+    /// `alt` or `value`
+    fn collecting_value(&self) -> bool {
+        matches!(
+            self.stack.last(),
+            Some(Node::Image { .. } | Node::Code { .. } | Node::Html { .. })
+        )
+    }
+
+    fn append_value(&mut self, text: &str) {
+        if let Some(
+            Node::Image { alt: value, .. } | Node::Code { value, .. } | Node::Html { value, .. },
+        ) = self.stack.last_mut()
+        {
+            value.push_str(text);
+        }
+    }
+
+    fn start(&mut self, tag: Tag, position: Position) {
+        if self.image_depth > 0 {
+            self.image_depth += 1;
+            return;
+        }
+        let node = match tag {
+            Tag::Paragraph => Node::Paragraph {
+                children: Vec::new(),
+                position,
+            },
+            Tag::Heading { level, .. } => {
+                let id = self.heading_ids.next().unwrap_or_default();
+                Node::Heading {
+                    depth: adjusted_heading_level(
+                        level,
+                        self.markdown_options.heading_level_offset,
+                        self.markdown_options.heading_one_policy,
+                    ) as u8,
+                    children: Vec::new(),
+                    data: HeadingData::new(&id),
+                    position,
+                }
+            }
+            Tag::BlockQuote(_) => Node::Blockquote {
+                children: Vec::new(),
+                position,
+            },
+            Tag::CodeBlock(kind) => {
+                let (lang, meta) = code_info(kind);
+                Node::Code {
+                    lang,
+                    meta,
+                    value: String::new(),
+                    position,
+                }
+            }
+            Tag::HtmlBlock => Node::Html {
+                value: String::new(),
+                position,
+            },
+            Tag::List(start) => Node::List {
+                ordered: start.is_some(),
+                start,
+                spread: false,
+                children: Vec::new(),
+                position,
+            },
+            Tag::Item => Node::ListItem {
+                spread: false,
+                checked: None,
+                children: Vec::new(),
+                position,
+            },
+            Tag::Table(alignments) => Node::Table {
+                align: alignments.into_iter().map(alignment_name).collect(),
+                children: Vec::new(),
+                position,
+            },
+            Tag::TableHead | Tag::TableRow => Node::TableRow {
+                children: Vec::new(),
+                position,
+            },
+            Tag::TableCell => Node::TableCell {
+                children: Vec::new(),
+                position,
+            },
+            Tag::Emphasis => Node::Emphasis {
+                children: Vec::new(),
+                position,
+            },
+            Tag::Strong => Node::Strong {
+                children: Vec::new(),
+                position,
+            },
+            Tag::Strikethrough => Node::Delete {
+                children: Vec::new(),
+                position,
+            },
+            Tag::Link {
+                dest_url, title, ..
+            } => Node::Link {
+                url: dest_url.to_string(),
+                title: (!title.is_empty()).then(|| title.to_string()),
+                children: Vec::new(),
+                position,
+            },
+            Tag::Image {
+                dest_url, title, ..
+            } => {
+                self.image_depth = 1;
+                Node::Image {
+                    url: dest_url.to_string(),
+                    title: (!title.is_empty()).then(|| title.to_string()),
+                    alt: String::new(),
+                    position,
+                }
+            }
+            _ => return,
+        };
+        self.stack.push(node);
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        if self.image_depth > 1 {
+            self.image_depth -= 1;
+            return;
+        }
+        self.image_depth = 0;
+        if !matches!(
+            tag,
+            TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::BlockQuote(_)
+                | TagEnd::CodeBlock
+                | TagEnd::HtmlBlock
+                | TagEnd::List(_)
+                | TagEnd::Item
+                | TagEnd::Table
+                | TagEnd::TableHead
+                | TagEnd::TableRow
+                | TagEnd::TableCell
+                | TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Link
+                | TagEnd::Image
+        ) {
+            return;
+        }
+        let Some(mut node) = self.stack.pop() else {
+            return;
+        };
+        match &mut node {
+            Node::Code { value, .. } | Node::Html { value, .. } if value.ends_with('\n') => {
+                value.pop();
+            }
+            // items of loose lists hold paragraphs, rather than bare text
+            Node::ListItem {
+                spread, children, ..
+            } => {
+                *spread = children
+                    .iter()
+                    .any(|child| matches!(child, Node::Paragraph { .. }));
+            }
+            Node::List {
+                spread, children, ..
+            } => {
+                *spread = children
+                    .iter()
+                    .any(|child| matches!(child, Node::ListItem { spread: true, .. }));
+            }
+            _ => {}
+        }
+        self.push_child(node);
+    }
+
+    fn event(&mut self, event: Event, range: &Range<usize>) {
+        let position = self.locator.position(range);
+        match event {
+            Event::Start(tag) => {
+                // block ranges include the line ending, which mdast positions leave out
+                let source = &self.locator.markdown[range.clone()];
+                let end = range.start + source.trim_end_matches(['\n', '\r']).len();
+                let position = self.locator.position(&(range.start..end));
+                self.start(tag, position);
+            }
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) | Event::Code(text) if self.collecting_value() => {
+                self.append_value(&text);
+            }
+            Event::SoftBreak if self.collecting_value() => self.append_value(" "),
+            Event::Html(html) => self.append_value(&html),
+            Event::Text(text) => self.push_child(Node::Text {
+                value: text.to_string(),
+                position,
+            }),
+            Event::Code(code) => self.push_child(Node::InlineCode {
+                value: code.to_string(),
+                position,
+            }),
+            Event::InlineHtml(html) => self.push_child(Node::Html {
+                value: html.to_string(),
+                position,
+            }),
+            Event::SoftBreak => self.push_child(Node::Text {
+                value: String::from("\n"),
+                position,
+            }),
+            Event::HardBreak => self.push_child(Node::Break { position }),
+            Event::Rule => self.push_child(Node::ThematicBreak { position }),
+            _ => {}
+        }
+    }
+}
+
+/// Syntax tree for `markdown`, in the unified mdast shape, with source positions and the heading
+/// ids used in the HTML output
+pub fn parse_markdown_to_mdast(markdown: &str, markdown_options: &ParseMarkdownOptions) -> Node {
+    let mut options = parser_options(markdown_options);
+    if markdown_options.locale.is_none() {
+        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
+    let locator = Locator::new(markdown);
+    let mut builder = TreeBuilder {
+        stack: vec![Node::Root {
+            children: Vec::new(),
+            position: locator.position(&(0..markdown.len())),
+        }],
+        locator,
+        heading_ids: heading_ids(markdown, options, markdown_options).into_iter(),
+        markdown_options,
+        image_depth: 0,
+    };
+    for (event, range) in Parser::new_ext(markdown, options).into_offset_iter() {
+        builder.event(event, &range);
+    }
+    builder.stack.swap_remove(0)
+}
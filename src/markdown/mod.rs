@@ -1,22 +1,34 @@
 #[cfg(test)]
 mod tests;
 
+mod abbreviation;
+mod commonmark;
+pub mod cross_reference;
+mod emoji;
+mod excerpt;
+mod mdast;
+mod plaintext;
+mod typography;
+
 use std::io::{self, Cursor};
 
 use deunicode::deunicode;
-use pulldown_cmark::{
-    CowStr,
-    Event::{self, Code, End, InlineHtml, SoftBreak, Start, Text},
-    Options, Parser, Tag, TagEnd, html,
-};
-use pulldown_cmark_escape::StrWrite;
-use serde::Serialize;
-use textwrap::wrap;
+use pulldown_cmark::{CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd, html};
+use serde::{Deserialize, Serialize};
+
+pub use abbreviation::extract_abbreviations;
+use commonmark::{ReferenceDefinition, push_html_markdown, push_markdown};
+use cross_reference::CrossReferences;
+pub use cross_reference::{CrossReference, CrossReferenceTemplates};
+use emoji::EmojiShortcodes;
+pub use excerpt::{Excerpt, markdown_excerpt};
+pub use mdast::parse_markdown_to_mdast;
+use plaintext::{push_html_plaintext, push_plaintext};
+use typography::Typography;
 
 use crate::{
-    inline_html::{InlineHTMLTagType, parse_node as parse_inline_html_node},
-    url_utility::relative_url,
-    utilities::stack::Stack,
+    text::{Locale, remove_emoji},
+    url_utility::UrlRewriter,
 };
 
 /// Reading time in minutes from number of words, assumes 180 wpm reading speed from a device
@@ -76,17 +88,20 @@ impl TextStatistics {
     }
 }
 
+#[allow(clippy::struct_field_names)]
 #[derive(Debug, Eq, PartialEq, Serialize)]
 pub struct Heading {
     heading: String,
     id: String,
+    level: u8,
 }
 
 impl Heading {
-    pub fn new(heading: &str, id: &str) -> Heading {
+    pub fn new(heading: &str, id: &str, level: u8) -> Heading {
         Heading {
             heading: heading.into(),
             id: id.into(),
+            level,
         }
     }
 
@@ -95,21 +110,124 @@ impl Heading {
     }
 }
 
+/// Handling of level one headings in the document body, for layouts which add the page title
+/// `<h1>` themselves
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingOnePolicy {
+    #[default]
+    Allow,
+
+    /// Renders level one headings as level two
+    Demote,
+
+    /// Fails parsing, with an error naming the heading
+    Reject,
+}
+
+/// `level` shifted by `offset`, clamped at `h6`, with level one headings demoted by `policy`
+fn adjusted_heading_level(
+    level: HeadingLevel,
+    offset: u8,
+    policy: HeadingOnePolicy,
+) -> HeadingLevel {
+    let mut value = (level as usize).saturating_add(usize::from(offset)).min(6);
+    if value == 1 && policy == HeadingOnePolicy::Demote {
+        value = 2;
+    }
+    HeadingLevel::try_from(value).unwrap_or(HeadingLevel::H6)
+}
+
+/// Markdown events with heading levels adjusted, emoji shortcodes expanded and locale typography
+/// applied, as configured in `markdown_options`
+fn markdown_events<'a>(
+    markdown: &'a str,
+    options: Options,
+    markdown_options: &ParseMarkdownOptions,
+) -> impl Iterator<Item = Event<'a>> {
+    let ParseMarkdownOptions {
+        heading_level_offset,
+        heading_one_policy,
+        ..
+    } = *markdown_options;
+    let parser =
+        Parser::new_ext(markdown, options).map(move |event| match event {
+            Event::Start(Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            }) => Event::Start(Tag::Heading {
+                level: adjusted_heading_level(level, heading_level_offset, heading_one_policy),
+                id,
+                classes,
+                attrs,
+            }),
+            Event::End(TagEnd::Heading(level)) => Event::End(TagEnd::Heading(
+                adjusted_heading_level(level, heading_level_offset, heading_one_policy),
+            )),
+            _ => event,
+        });
+    Typography::new(
+        EmojiShortcodes::new(parser, markdown_options.enable_emoji_shortcodes),
+        markdown_options.locale,
+    )
+}
+
+/// Id for a heading with text `heading`, where an author `{#id}` takes precedence over the
+/// generated slug
+fn heading_id(
+    heading: &str,
+    custom_id: Option<String>,
+    markdown_options: &ParseMarkdownOptions,
+) -> String {
+    if let Some(value) = custom_id {
+        value
+    } else if markdown_options.drop_emoji_from_slugs {
+        slugified_title(remove_emoji(heading).trim())
+    } else {
+        slugified_title(heading)
+    }
+}
+
+/// Markdown syntax extensions enabled for `markdown_options`
+fn parser_options(markdown_options: &ParseMarkdownOptions) -> Options {
+    let mut options = Options::ENABLE_HEADING_ATTRIBUTES;
+    if markdown_options.enable_gfm {
+        options.insert(Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH);
+    }
+    if markdown_options
+        .cross_reference_templates
+        .wiki_link
+        .is_some()
+    {
+        options.insert(Options::ENABLE_WIKILINKS);
+    }
+    options
+}
+
 pub fn parse_markdown_to_html(
     markdown: &str,
-) -> io::Result<(String, Vec<Heading>, TextStatistics)> {
+    markdown_options: &ParseMarkdownOptions,
+) -> io::Result<(String, Vec<Heading>, TextStatistics, Vec<CrossReference>)> {
     let mut bytes = Vec::new();
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    let mut options = parser_options(markdown_options);
+    // locale typography replaces the parser's English smart punctuation
+    if markdown_options.locale.is_none() {
+        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
 
     let mut headings: Vec<Heading> = Vec::new();
     let mut current_id_fragments = String::new();
+    let mut current_custom_id: Option<String> = None;
     let mut parsing_heading = false;
     let mut word_count: u32 = 0;
 
-    let heading_parser = Parser::new_ext(markdown, options).inspect(|event| match &event {
-        Event::Start(Tag::Heading { .. }) => {
+    let events = markdown_events(markdown, options, markdown_options);
+    let heading_parser = events.inspect(|event| match &event {
+        Event::Start(Tag::Heading { id, .. }) => {
             parsing_heading = true;
+            current_custom_id = id.as_ref().map(ToString::to_string);
         }
         Event::Text(value) => {
             word_count += words(value);
@@ -122,16 +240,27 @@ pub fn parse_markdown_to_html(
                 current_id_fragments.push_str(value);
             }
         }
-        Event::End(TagEnd::Heading(_heading_level)) => {
+        Event::End(TagEnd::Heading(level)) => {
             let heading = &current_id_fragments;
-            let id = slugified_title(&current_id_fragments);
-            headings.push(Heading::new(heading, &id));
+            let id = heading_id(heading, current_custom_id.take(), markdown_options);
+            headings.push(Heading::new(heading, &id, *level as u8));
             current_id_fragments = String::new();
             parsing_heading = false;
         }
         _ => {}
     });
     html::write_html_io(Cursor::new(&mut bytes), heading_parser)?;
+    if markdown_options.heading_one_policy == HeadingOnePolicy::Reject {
+        if let Some(heading) = headings.iter().find(|heading| heading.level == 1) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Level one heading \"{}\" is not allowed in the document body",
+                    heading.heading
+                ),
+            ));
+        }
+    }
     let reading_time = reading_time_from_words(word_count);
     let statistics = TextStatistics {
         reading_time,
@@ -139,219 +268,141 @@ pub fn parse_markdown_to_html(
     };
 
     let mut heading_iterator = headings.iter();
-    let parser = Parser::new_ext(markdown, options).map(|event| match &event {
-        Event::Start(Tag::Heading { level, .. }) => {
+    let mut cross_references = Vec::new();
+    let parser = CrossReferences::new(
+        markdown_events(markdown, options, markdown_options),
+        &markdown_options.cross_reference_templates,
+        &mut cross_references,
+    )
+    .map(|event| match &event {
+        Event::Start(Tag::Heading {
+            level,
+            classes,
+            attrs,
+            ..
+        }) => {
             let heading_identifier = heading_iterator.next();
             Event::Start(Tag::Heading {
                 level: *level,
                 id: heading_identifier.map(|val| CowStr::from(val.id())),
-                classes: Vec::new(),
-                attrs: Vec::new(),
+                classes: classes.clone(),
+                attrs: attrs.clone(),
             })
         }
         _ => event,
     });
 
+    // cross references can shorten the output, so no first pass bytes may be left over
+    bytes.clear();
     match html::write_html_io(Cursor::new(&mut bytes), parser) {
         Ok(()) => Ok((
             String::from_utf8_lossy(&bytes).to_string(),
             headings,
             statistics,
+            cross_references,
         )),
         Err(error) => Err(error),
     }
 }
 
-struct PlaintextWriter<'a, I, W> {
-    /// Iterator supplying events.
-    iter: I,
-
-    /// Writer to write to.
-    writer: W,
+/// Link destinations in `markdown`, in source order, each with its, one-based, line number
+pub fn link_source_lines(markdown: &str) -> Vec<(String, usize)> {
+    let mut line = 1;
+    let mut line_counted_to = 0;
+    Parser::new_ext(markdown, Options::empty())
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                line += markdown[line_counted_to..range.start].matches('\n').count();
+                line_counted_to = range.start;
+                Some((dest_url.to_string(), line))
+            }
+            _ => None,
+        })
+        .collect()
+}
 
-    /// Whether the last write created a newline
-    end_newline: bool,
+/// Placement of link URLs in plaintext output
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlaintextLinkStyle {
+    /// `text (https://example.com)`
+    #[default]
+    Inline,
 
-    /// Buffer of words in current line of input, gets wrapped to preferred length before output
-    current_line: String,
+    /// `text [1]`, with numbered references listed at the end of the document
+    Reference,
 
-    current_link: Option<String>,
+    /// `text [1]`, with numbered references listed at the end of each section, ahead of the next
+    /// heading
+    SectionReference,
+}
 
-    /// Preferred length of wrapped out lines, currently fixed at 72
-    line_length: usize,
+/// Marking of headings in plaintext output
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaintextHeadingStyle {
+    /// Heading text on its own line, like a paragraph
+    #[default]
+    Plain,
 
-    /// HTML tags to ignore in output
-    ignore_tags: Vec<&'a str>,
+    /// Heading text underlined with `=` for level one headings, and `-` for others
+    Setext,
 
-    /// Optionally prepended to relative URLs
-    canonical_root_url: Option<&'a str>,
+    /// Heading text in capitals
+    Uppercase,
 }
 
-impl<'a, I, W> PlaintextWriter<'a, I, W>
-where
-    I: Iterator<Item = Event<'a>>,
-    W: StrWrite<Error = std::fmt::Error>,
-{
-    fn new(iter: I, writer: W, canonical_root_url: Option<&'a str>) -> Self {
-        Self {
-            iter,
-            writer,
-            end_newline: true,
-            current_line: String::new(),
-            current_link: None,
-            line_length: 72,
-            ignore_tags: vec!["tool-tip"],
-            canonical_root_url,
-        }
-    }
+/// Marking of emphasis in plaintext output
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaintextEmphasisStyle {
+    /// Emphasis markers dropped
+    #[default]
+    None,
 
-    /// Writes a new line.
-    fn write_newline(&mut self) -> Result<(), std::fmt::Error> {
-        self.end_newline = true;
-        self.writer.write_str("\n")
-    }
+    /// `*emphasis*` and `**strong emphasis**`
+    Asterisk,
 
-    /// Wraps the current line on input to preferred length and writes the wrapped lines
-    #[inline]
-    fn write(&mut self) -> Result<(), std::fmt::Error> {
-        let lines = wrap(&self.current_line, self.line_length);
-        for line in &lines {
-            self.writer.write_str(line)?;
-            self.writer.write_str("\n")?;
-        }
+    /// `_emphasis_` and `__strong emphasis__`
+    Underscore,
+}
 
-        self.current_line = String::new();
-        if !self.current_line.is_empty() {
-            self.end_newline = self.current_line.ends_with('\n');
-        }
-        Ok(())
-    }
-
-    fn run(mut self) -> Result<(), std::fmt::Error> {
-        while let Some(event) = self.iter.next() {
-            match event {
-                Start(tag) => {
-                    self.start_tag(tag)?;
-                }
-                End(tag) => {
-                    self.end_tag(tag)?;
-                }
-                Text(text) | Code(text) => {
-                    self.current_line.push_str(&text);
-                    self.end_newline = text.ends_with('\n');
-                }
-                InlineHtml(inline_html) => {
-                    if let Some(InlineHTMLTagType::Opening(value)) =
-                        parse_inline_html_node(&inline_html)
-                    {
-                        if self.ignore_tags.contains(&value.as_ref()) {
-                            let mut open_tags: Stack<String> = Stack::new();
-                            open_tags.push(value);
-                            for html_event in self.iter.by_ref() {
-                                if let InlineHtml(nested_inline_html) = html_event {
-                                    match parse_inline_html_node(&nested_inline_html) {
-                                        Some(InlineHTMLTagType::Opening(open_tag_value)) => {
-                                            open_tags.push(open_tag_value);
-                                        }
-                                        Some(InlineHTMLTagType::Closing(closing_tag_value)) => {
-                                            if let Some(popped_value) = open_tags.pop() {
-                                                if popped_value == closing_tag_value
-                                                    && open_tags.is_empty()
-                                                {
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                        None => {}
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                SoftBreak => {
-                    self.current_line.push(' ');
-                }
-                _ => {}
-            }
-        }
-        Ok(())
-    }
-
-    /// Handles the start of an HTML tag.
-    fn start_tag(&mut self, tag: Tag) -> Result<(), std::fmt::Error> {
-        match tag {
-            Tag::Paragraph => {
-                if self.end_newline {
-                    Ok(())
-                } else {
-                    self.write()
-                }
-            }
-            Tag::Heading { .. } => {
-                if self.end_newline {
-                    self.end_newline = false;
-                    Ok(())
-                } else {
-                    self.write()?;
-                    self.write_newline()
-                }
-            }
-            Tag::Item => {
-                if self.end_newline {
-                    self.current_line.push_str("- ");
-                } else {
-                    self.current_line.push_str("\n- ");
-                }
-                Ok(())
-            }
-            Tag::Link { dest_url, .. } => {
-                self.current_link = Some(dest_url.to_string());
-                Ok(())
-            }
-            _ => Ok(()),
-        }
-    }
+/// Plaintext handling of an HTML element and its content
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HtmlElementRule<'a> {
+    /// Leaves out the element and its content
+    Drop,
 
-    fn end_tag(&mut self, tag: TagEnd) -> Result<(), std::fmt::Error> {
-        match tag {
-            TagEnd::Paragraph | TagEnd::Item => {
-                self.write()?;
-            }
-            TagEnd::Heading(_level) => {
-                self.write()?;
-            }
-            TagEnd::Link => {
-                if let Some(value) = &self.current_link {
-                    self.current_line.push_str(" (");
-                    if let Some(root_url_value) = self.canonical_root_url {
-                        if relative_url(value) {
-                            self.current_line.push_str(root_url_value);
-                        }
-                    }
-                    self.current_line.push_str(value);
-                    self.current_line.push(')');
-                }
-            }
-            _ => {}
-        }
-        Ok(())
-    }
-}
+    /// Keeps the text content, used for elements with no rule
+    KeepText,
 
-fn push_plaintext<'a, I>(s: &mut String, iter: I, canonical_root_url: Option<&'a str>)
-where
-    I: Iterator<Item = Event<'a>>,
-{
-    PlaintextWriter::new(iter, s, canonical_root_url)
-        .run()
-        .unwrap();
+    /// Replaces the element with the value of the named attribute, so `<abbr title="…">` can be
+    /// expanded.  Text content is kept when the attribute is missing.
+    Attribute(&'a str),
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub struct ParseMarkdownOptions<'a> {
     canonical_root_url: Option<&'a str>,
     enable_smart_punctuation: bool,
+    locale: Option<Locale>,
+    url_rewriter: UrlRewriter<'a>,
+    cross_reference_templates: CrossReferenceTemplates<'a>,
+    enable_gfm: bool,
+    enable_emoji_shortcodes: bool,
+    drop_emoji_from_slugs: bool,
+    heading_level_offset: u8,
+    heading_one_policy: HeadingOnePolicy,
+    excerpt_words: usize,
+    description_length: usize,
+    line_length: Option<usize>,
+    link_style: PlaintextLinkStyle,
+    heading_style: PlaintextHeadingStyle,
+    emphasis_style: PlaintextEmphasisStyle,
+    label_images: bool,
+    html_element_rules: Vec<(&'a str, HtmlElementRule<'a>)>,
 }
 
 impl Default for ParseMarkdownOptions<'_> {
@@ -359,6 +410,22 @@ impl Default for ParseMarkdownOptions<'_> {
         ParseMarkdownOptions {
             canonical_root_url: None,
             enable_smart_punctuation: true,
+            locale: None,
+            url_rewriter: UrlRewriter::default(),
+            cross_reference_templates: CrossReferenceTemplates::default(),
+            enable_gfm: false,
+            enable_emoji_shortcodes: false,
+            drop_emoji_from_slugs: false,
+            heading_level_offset: 0,
+            heading_one_policy: HeadingOnePolicy::Allow,
+            excerpt_words: 55,
+            description_length: 160,
+            line_length: Some(72),
+            link_style: PlaintextLinkStyle::Inline,
+            heading_style: PlaintextHeadingStyle::Plain,
+            emphasis_style: PlaintextEmphasisStyle::None,
+            label_images: false,
+            html_element_rules: vec![("tool-tip", HtmlElementRule::Drop)],
         }
     }
 }
@@ -373,21 +440,197 @@ impl<'a> ParseMarkdownOptions<'a> {
         self.enable_smart_punctuation = value;
         self
     }
+
+    /// Locale typography for quotes, dashes and punctuation spacing, in place of smart punctuation
+    pub fn locale(&mut self, value: Option<Locale>) -> &mut Self {
+        self.locale = value;
+        self
+    }
+
+    pub fn url_rewriter(&mut self, value: UrlRewriter<'a>) -> &mut Self {
+        self.url_rewriter = value;
+        self
+    }
+
+    /// URL templates for `[[Page Name]]` wiki-links, `@username` mentions and `#topic` hashtags
+    /// in HTML output
+    pub fn cross_reference_templates(&mut self, value: CrossReferenceTemplates<'a>) -> &mut Self {
+        self.cross_reference_templates = value;
+        self
+    }
+
+    /// Parses GitHub Flavored Markdown tables and `~~strikethrough~~`, as written by
+    /// `parse_html_to_markdown`
+    pub fn enable_gfm(&mut self, value: bool) -> &mut Self {
+        self.enable_gfm = value;
+        self
+    }
+
+    /// Replaces GitHub-style shortcodes, like `:rocket:`, with emoji in text outside code
+    pub fn enable_emoji_shortcodes(&mut self, value: bool) -> &mut Self {
+        self.enable_emoji_shortcodes = value;
+        self
+    }
+
+    /// Leaves emoji out of heading ids, instead of transliterating them, so `Hi 👋🏽` gets `hi`
+    /// rather than `hi-wave-`
+    pub fn drop_emoji_from_slugs(&mut self, value: bool) -> &mut Self {
+        self.drop_emoji_from_slugs = value;
+        self
+    }
+
+    /// Shifts every heading down `value` levels, clamped at `h6`, so `# Title` becomes `<h2>` for
+    /// an offset of one
+    pub fn heading_level_offset(&mut self, value: u8) -> &mut Self {
+        self.heading_level_offset = value;
+        self
+    }
+
+    /// Allows, demotes or rejects level one headings, after any level offset is applied
+    pub fn heading_one_policy(&mut self, value: HeadingOnePolicy) -> &mut Self {
+        self.heading_one_policy = value;
+        self
+    }
+
+    /// Word limit for excerpts from documents with no `<!-- more -->` marker, defaults to 55
+    pub fn excerpt_words(&mut self, value: usize) -> &mut Self {
+        self.excerpt_words = value;
+        self
+    }
+
+    /// Character limit for the excerpt description, defaults to 160
+    pub fn description_length(&mut self, value: usize) -> &mut Self {
+        self.description_length = value;
+        self
+    }
+
+    /// Preferred plaintext line length, defaults to 72.  `None` leaves lines unwrapped.
+    pub fn line_length(&mut self, value: Option<usize>) -> &mut Self {
+        self.line_length = value;
+        self
+    }
+
+    /// Placement of link URLs in plaintext output
+    pub fn link_style(&mut self, value: PlaintextLinkStyle) -> &mut Self {
+        self.link_style = value;
+        self
+    }
+
+    /// Marking of headings in plaintext output
+    pub fn heading_style(&mut self, value: PlaintextHeadingStyle) -> &mut Self {
+        self.heading_style = value;
+        self
+    }
+
+    /// Marking of emphasis in plaintext output
+    pub fn emphasis_style(&mut self, value: PlaintextEmphasisStyle) -> &mut Self {
+        self.emphasis_style = value;
+        self
+    }
+
+    /// Writes images in plaintext as `[Image: alt text]`, rather than just the alt text
+    pub fn label_images(&mut self, value: bool) -> &mut Self {
+        self.label_images = value;
+        self
+    }
+
+    /// Plaintext handling for inline HTML elements, by tag name, replacing the default rule which
+    /// drops `<tool-tip>` elements
+    pub fn html_element_rules(&mut self, value: Vec<(&'a str, HtmlElementRule<'a>)>) -> &mut Self {
+        self.html_element_rules = value;
+        self
+    }
+
+    /// Rule for the HTML element `name`, with the first matching rule used
+    fn html_element_rule(&self, name: &str) -> HtmlElementRule<'a> {
+        self.html_element_rules
+            .iter()
+            .find(|(tag_name, _)| tag_name.eq_ignore_ascii_case(name))
+            .map_or(HtmlElementRule::KeepText, |(_, rule)| *rule)
+    }
 }
 
 pub fn parse_markdown_to_plaintext(markdown: &str, options: &ParseMarkdownOptions) -> String {
     let ParseMarkdownOptions {
-        canonical_root_url,
         enable_smart_punctuation,
+        locale,
+        enable_emoji_shortcodes,
+        ..
     } = options;
 
-    let mut parser_options = Options::empty();
-    if *enable_smart_punctuation {
+    let mut parser_options = Options::ENABLE_HEADING_ATTRIBUTES;
+    if *enable_smart_punctuation && locale.is_none() {
         parser_options.insert(Options::ENABLE_SMART_PUNCTUATION);
     }
-    let parser = Parser::new_ext(markdown, parser_options);
+    let locale = if *enable_smart_punctuation {
+        *locale
+    } else {
+        None
+    };
+    let parser = Typography::new(
+        EmojiShortcodes::new(
+            Parser::new_ext(markdown, parser_options),
+            *enable_emoji_shortcodes,
+        ),
+        locale,
+    );
 
     let mut plaintext_buf = String::new();
-    push_plaintext(&mut plaintext_buf, parser, *canonical_root_url);
+    push_plaintext(&mut plaintext_buf, parser, options);
     plaintext_buf
 }
+
+/// Plaintext from an HTML fragment, wrapped and laid out like `parse_markdown_to_plaintext` output,
+/// with links resolved against any canonical root URL
+pub fn parse_html_to_plaintext(html: &str, options: &ParseMarkdownOptions) -> String {
+    let mut plaintext_buf = String::new();
+    push_html_plaintext(&mut plaintext_buf, html, options);
+    plaintext_buf
+}
+
+/// `CommonMark`, with GFM tables and strikethrough, from an HTML fragment.  Elements with no
+/// Markdown counterpart are kept as HTML.
+pub fn parse_html_to_markdown(html: &str) -> String {
+    let mut markdown_buf = String::new();
+    push_html_markdown(&mut markdown_buf, html);
+    markdown_buf
+}
+
+/// `markdown` re-written in a consistent style: ATX headings, `-` bullets, fenced code blocks,
+/// padded tables, paragraphs wrapped to the `line_length` option and link reference definitions
+/// collected at the end.  Text is not changed, so typography and emoji options are not applied.
+pub fn parse_markdown_to_markdown(markdown: &str, options: &ParseMarkdownOptions) -> String {
+    let mut parser = Parser::new_ext(markdown, parser_options(options));
+    let events: Vec<Event> = parser.by_ref().collect();
+    let mut definitions: Vec<(usize, ReferenceDefinition)> = parser
+        .reference_definitions()
+        .iter()
+        .map(|(label, definition)| {
+            (
+                definition.span.start,
+                ReferenceDefinition {
+                    label: label.to_string(),
+                    dest_url: definition.dest.to_string(),
+                    title: definition
+                        .title
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                },
+            )
+        })
+        .collect();
+    definitions.sort_by_key(|(start, _)| *start);
+
+    let mut markdown_buf = String::new();
+    push_markdown(
+        &mut markdown_buf,
+        events.into_iter(),
+        options.line_length,
+        definitions
+            .into_iter()
+            .map(|(_, definition)| definition)
+            .collect(),
+    );
+    markdown_buf
+}
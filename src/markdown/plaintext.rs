@@ -0,0 +1,635 @@
+mod html;
+
+use std::borrow::Cow;
+
+use pulldown_cmark::{
+    CodeBlockKind,
+    Event::{self, Code, End, HardBreak, Html, InlineHtml, Rule, SoftBreak, Start, Text},
+    HeadingLevel, Tag, TagEnd,
+};
+use pulldown_cmark_escape::StrWrite;
+use textwrap::{Options, WordSeparator, WordSplitter, core::display_width, wrap};
+
+use super::{
+    HtmlElementRule, ParseMarkdownOptions, PlaintextEmphasisStyle, PlaintextHeadingStyle,
+    PlaintextLinkStyle,
+};
+use crate::{
+    inline_html::{InlineHTMLTagType, attribute_value, parse_node as parse_inline_html_node},
+    url_utility::relative_url,
+    utilities::stack::Stack,
+};
+
+/// Stands in for spaces in inline code while wrapping, so code spans are kept on a single line
+pub(super) const UNBREAKABLE_SPACE: char = '\u{e000}';
+
+/// HTML elements which have no content or closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Whether the element opened by `html` has no content, being void or self-closing
+fn empty_element(name: &str, html: &str) -> bool {
+    VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str()) || html.trim_end().ends_with("/>")
+}
+
+/// Block containing the text currently being written, which adds a prefix to each output line
+enum Container {
+    BlockQuote,
+    List {
+        /// Number for the next item of an ordered list
+        next_number: Option<u64>,
+
+        /// Whether items are separated by blank lines
+        loose: bool,
+
+        /// Whether an item has been started
+        started: bool,
+    },
+    Item {
+        marker: String,
+
+        /// Whether the marker is still to be written, on the first line of the item
+        marker_pending: bool,
+    },
+}
+
+struct PlaintextWriter<'a, I, W> {
+    /// Iterator supplying events.
+    iter: I,
+
+    /// Writer to write to.
+    writer: W,
+
+    /// Whether a blank line is due before the next block
+    blank_line_needed: bool,
+
+    /// Blocks containing the current text, outermost first
+    containers: Vec<Container>,
+
+    /// Buffer of words in current line of input, gets wrapped to preferred length before output
+    current_line: String,
+
+    /// Destination of the current link, and the offset of its text in the current line
+    current_link: Option<(String, usize)>,
+
+    /// URLs referred to from `text [1]` style links, waiting to be listed
+    references: Vec<String>,
+
+    /// Lines of the current block of HTML
+    html_block: String,
+
+    /// Whether any line has been written
+    started: bool,
+
+    /// Whether the current text is in a heading
+    in_heading: bool,
+
+    /// Kind of code block the current text is in, written verbatim
+    code_block: Option<CodeBlockKind<'a>>,
+
+    /// Line length, HTML element rules, link style, canonical root URL and URL rewriting choices
+    options: &'a ParseMarkdownOptions<'a>,
+}
+
+impl<'a, I, W> PlaintextWriter<'a, I, W>
+where
+    I: Iterator<Item = Event<'a>>,
+    W: StrWrite<Error = std::fmt::Error>,
+{
+    fn new(iter: I, writer: W, options: &'a ParseMarkdownOptions<'a>) -> Self {
+        Self {
+            iter,
+            writer,
+            blank_line_needed: false,
+            containers: Vec::new(),
+            current_line: String::new(),
+            current_link: None,
+            references: Vec::new(),
+            code_block: None,
+            html_block: String::new(),
+            started: false,
+            in_heading: false,
+            options,
+        }
+    }
+
+    /// Prefix for the next output line, from blockquote markers and list item markers or their
+    /// indent.  Item markers are only written once.
+    fn line_prefix(&mut self) -> String {
+        let mut result = String::new();
+        for container in &mut self.containers {
+            match container {
+                Container::BlockQuote => result.push_str("> "),
+                Container::List { .. } => {}
+                Container::Item {
+                    marker,
+                    marker_pending,
+                } => {
+                    if *marker_pending {
+                        result.push_str(marker);
+                        *marker_pending = false;
+                    } else {
+                        result.push_str(&" ".repeat(marker.len()));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Prefix for lines following the next one
+    fn continuation_prefix(&self) -> String {
+        self.containers
+            .iter()
+            .map(|container| match container {
+                Container::BlockQuote => String::from("> "),
+                Container::List { .. } => String::new(),
+                Container::Item { marker, .. } => " ".repeat(marker.len()),
+            })
+            .collect()
+    }
+
+    /// Writes a single line, with trailing spaces removed
+    fn write_line(&mut self, line: &str) -> Result<(), std::fmt::Error> {
+        self.started = true;
+        self.writer.write_str(line.trim_end_matches(' '))?;
+        self.writer.write_str("\n")
+    }
+
+    /// Writes a blank line, if one is due, ahead of a new block
+    fn write_separator(&mut self) -> Result<(), std::fmt::Error> {
+        if self.blank_line_needed {
+            self.blank_line_needed = false;
+            if !self.started {
+                return Ok(());
+            }
+            let prefix = self.continuation_prefix();
+            self.write_line(&prefix)?;
+        }
+        Ok(())
+    }
+
+    /// Wraps the current line on input to preferred length and writes the wrapped lines.  Hard
+    /// breaks, saved as newlines, start a new line.  Returns the width of the longest line
+    /// written.
+    fn write(&mut self) -> Result<usize, std::fmt::Error> {
+        let text = std::mem::take(&mut self.current_line);
+        let text = text.trim_matches(' ');
+        let mut width = 0;
+        if text.is_empty() {
+            return Ok(width);
+        }
+        for segment in text.split('\n') {
+            let initial_indent = self.line_prefix();
+            let subsequent_indent = self.continuation_prefix();
+            let lines: Vec<Cow<str>> = match self.options.line_length {
+                Some(line_length) => {
+                    // lines only break at spaces, so URLs and hyphenated words are never split
+                    let options = Options::new(line_length)
+                        .word_separator(WordSeparator::AsciiSpace)
+                        .word_splitter(WordSplitter::NoHyphenation)
+                        .break_words(false)
+                        .initial_indent(&initial_indent)
+                        .subsequent_indent(&subsequent_indent);
+                    wrap(segment.trim_start_matches(' '), options)
+                }
+                None => vec![Cow::Owned(format!(
+                    "{initial_indent}{}",
+                    segment.trim_start_matches(' ')
+                ))],
+            };
+            for line in lines {
+                let line = line.replace(UNBREAKABLE_SPACE, " ");
+                width = width.max(display_width(line.trim_end_matches(' ')));
+                self.write_line(&line)?;
+            }
+        }
+        Ok(width)
+    }
+
+    /// Writes any pending text, then a blank line if due, ahead of a new block
+    fn start_block(&mut self) -> Result<(), std::fmt::Error> {
+        self.write()?;
+        self.write_separator()
+    }
+
+    /// Writes a setext style underline, matching the width of the heading text
+    fn write_heading_underline(
+        &mut self,
+        level: HeadingLevel,
+        width: usize,
+    ) -> Result<(), std::fmt::Error> {
+        let prefix = self.continuation_prefix();
+        let character = if level == HeadingLevel::H1 { "=" } else { "-" };
+        let underline = character.repeat(width.saturating_sub(display_width(&prefix)).max(1));
+        self.write_line(&format!("{prefix}{underline}"))
+    }
+
+    /// Marker for emphasis, doubled for strong emphasis, or an empty string when emphasis is not
+    /// shown
+    fn emphasis_marker(&self, strong: bool) -> String {
+        let marker = match self.options.emphasis_style {
+            PlaintextEmphasisStyle::None => "",
+            PlaintextEmphasisStyle::Asterisk => "*",
+            PlaintextEmphasisStyle::Underscore => "_",
+        };
+        if strong {
+            marker.repeat(2)
+        } else {
+            marker.to_string()
+        }
+    }
+
+    /// `destination` made absolute, using any canonical root URL, and with query parameters
+    /// rewritten
+    fn resolved_url(&self, destination: &str) -> String {
+        let mut url = String::new();
+        if let Some(root_url_value) = self.options.canonical_root_url {
+            if relative_url(destination) {
+                url.push_str(root_url_value);
+            }
+        }
+        url.push_str(destination);
+        if let Some(rewritten_url) = self.options.url_rewriter.rewrite(&url) {
+            url = rewritten_url;
+        }
+        url
+    }
+
+    /// Adds the link destination after the link text, either inline or as a numbered reference.
+    /// References are not added for in-page fragment links, or where the link text already shows
+    /// the destination.
+    fn write_link_destination(&mut self, destination: &str, text_start: usize) {
+        if self.options.link_style == PlaintextLinkStyle::Inline {
+            let url = self.resolved_url(destination);
+            self.current_line.push_str(" (");
+            self.current_line.push_str(&url);
+            self.current_line.push(')');
+            return;
+        }
+        if destination.starts_with('#') {
+            return;
+        }
+        let url = match destination.strip_prefix("mailto:") {
+            Some(address) => address.to_string(),
+            None => self.resolved_url(destination),
+        };
+        let text = self.current_line[text_start..].trim();
+        if text == url || text == destination {
+            return;
+        }
+        let number = if let Some(index) = self.references.iter().position(|value| *value == url) {
+            index + 1
+        } else {
+            self.references.push(url);
+            self.references.len()
+        };
+        self.current_line.push_str(" [");
+        self.current_line.push_str(&number.to_string());
+        self.current_line.push(']');
+    }
+
+    /// Writes the numbered list of references collected so far, and restarts numbering
+    fn write_references(&mut self) -> Result<(), std::fmt::Error> {
+        if self.references.is_empty() {
+            return Ok(());
+        }
+        self.start_block()?;
+        for (index, url) in std::mem::take(&mut self.references).iter().enumerate() {
+            self.write_line(&format!("[{}] {url}", index + 1))?;
+        }
+        self.blank_line_needed = true;
+        Ok(())
+    }
+
+    /// Applies the rule for the element opened by `html`, if any.  Text in elements with no rule
+    /// is kept.
+    fn inline_html(&mut self, html: &str) -> Result<(), std::fmt::Error> {
+        let Some(InlineHTMLTagType::Opening(name)) = parse_inline_html_node(html) else {
+            return Ok(());
+        };
+        let has_content = !empty_element(&name, html);
+        match self.options.html_element_rule(&name) {
+            HtmlElementRule::KeepText => {}
+            HtmlElementRule::Drop => {
+                if has_content {
+                    self.skip_element(name)?;
+                }
+            }
+            HtmlElementRule::Attribute(attribute) => {
+                if let Some(value) = attribute_value(html, attribute) {
+                    self.current_line.push_str(&value);
+                    if has_content {
+                        self.skip_element(name)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips events up to the closing tag of the element `name`, or to the end of the enclosing
+    /// block, where the element is left unclosed
+    fn skip_element(&mut self, name: String) -> Result<(), std::fmt::Error> {
+        let mut open_tags: Stack<String> = Stack::new();
+        open_tags.push(name);
+        let mut depth = 0;
+        while let Some(event) = self.iter.next() {
+            match event {
+                Start(_) => depth += 1,
+                End(tag) => {
+                    if depth == 0 {
+                        return self.end_tag(tag);
+                    }
+                    depth -= 1;
+                }
+                InlineHtml(nested_inline_html) => {
+                    match parse_inline_html_node(&nested_inline_html) {
+                        Some(InlineHTMLTagType::Opening(open_tag_value))
+                            if !empty_element(&open_tag_value, &nested_inline_html) =>
+                        {
+                            open_tags.push(open_tag_value);
+                        }
+                        Some(InlineHTMLTagType::Closing(closing_tag_value)) => {
+                            if let Some(popped_value) = open_tags.pop() {
+                                if popped_value == closing_tag_value && open_tags.is_empty() {
+                                    break;
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn run(mut self) -> Result<(), std::fmt::Error> {
+        while let Some(event) = self.iter.next() {
+            match event {
+                Start(tag) => {
+                    self.start_tag(tag)?;
+                }
+                End(tag) => {
+                    self.end_tag(tag)?;
+                }
+                Text(text) => {
+                    self.push_text(&text);
+                }
+                Code(text) => {
+                    self.push_code(&text);
+                }
+                Html(html) => {
+                    self.html_block.push_str(&html);
+                }
+                InlineHtml(inline_html) => {
+                    self.inline_html(&inline_html)?;
+                }
+                SoftBreak => {
+                    self.current_line.push(' ');
+                }
+                HardBreak => {
+                    self.current_line.push('\n');
+                }
+                Rule => {
+                    self.write_rule()?;
+                }
+                _ => {}
+            }
+        }
+        self.write_references()
+    }
+
+    /// Handles the start of an HTML tag.
+    fn start_tag(&mut self, tag: Tag<'a>) -> Result<(), std::fmt::Error> {
+        match tag {
+            Tag::Paragraph => self.start_paragraph(),
+            Tag::Heading { .. } => self.start_heading(),
+            Tag::Emphasis | Tag::Strong => {
+                let marker = self.emphasis_marker(tag == Tag::Strong);
+                self.current_line.push_str(&marker);
+                Ok(())
+            }
+            Tag::Image { .. } => {
+                if self.options.label_images {
+                    self.current_line.push_str("[Image: ");
+                }
+                Ok(())
+            }
+            Tag::BlockQuote(_) => self.start_container(Container::BlockQuote),
+            Tag::CodeBlock(kind) => {
+                self.start_block()?;
+                if let CodeBlockKind::Fenced(ref info) = kind {
+                    let prefix = self.line_prefix();
+                    self.write_line(&format!("{prefix}```{info}"))?;
+                }
+                self.code_block = Some(kind);
+                Ok(())
+            }
+            Tag::List(start) => self.start_container(Container::List {
+                next_number: start,
+                loose: false,
+                started: false,
+            }),
+            Tag::Item => self.start_item(),
+            Tag::Link { dest_url, .. } => {
+                self.current_link = Some((dest_url.to_string(), self.current_line.len()));
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn end_tag(&mut self, tag: TagEnd) -> Result<(), std::fmt::Error> {
+        match tag {
+            TagEnd::Paragraph => self.end_block()?,
+            TagEnd::Heading(level) => self.end_heading(level)?,
+            TagEnd::Emphasis | TagEnd::Strong => {
+                let marker = self.emphasis_marker(tag == TagEnd::Strong);
+                self.current_line.push_str(&marker);
+            }
+            TagEnd::Image if self.options.label_images => self.end_image_label(),
+            TagEnd::BlockQuote(_) | TagEnd::List(_) => self.end_container()?,
+            TagEnd::CodeBlock => {
+                let code = std::mem::take(&mut self.current_line);
+                let fenced = matches!(self.code_block.take(), Some(CodeBlockKind::Fenced(_)));
+                let indent = if fenced { "" } else { "    " };
+                self.write_verbatim(&code, indent)?;
+                if fenced {
+                    let prefix = self.line_prefix();
+                    self.write_line(&format!("{prefix}```"))?;
+                }
+                self.blank_line_needed = true;
+            }
+            TagEnd::HtmlBlock => {
+                let html = std::mem::take(&mut self.html_block);
+                self.write_html(&html)?;
+            }
+            TagEnd::Item => self.end_item()?,
+            TagEnd::Link => {
+                if let Some((destination, text_start)) = self.current_link.take() {
+                    self.write_link_destination(&destination, text_start);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Adds text to the current line, in capitals for headings when that style is chosen
+    fn push_text(&mut self, text: &str) {
+        if self.in_heading && self.options.heading_style == PlaintextHeadingStyle::Uppercase {
+            self.current_line.push_str(&text.to_uppercase());
+        } else {
+            self.current_line.push_str(text);
+        }
+    }
+
+    /// Adds inline code to the current line, kept on a single line when wrapping
+    fn push_code(&mut self, code: &str) {
+        self.current_line
+            .push_str(&code.replace(' ', &UNBREAKABLE_SPACE.to_string()));
+    }
+
+    fn start_paragraph(&mut self) -> Result<(), std::fmt::Error> {
+        if let [.., Container::List { loose, .. }, Container::Item { .. }] =
+            &mut self.containers[..]
+        {
+            *loose = true;
+        }
+        self.start_block()
+    }
+
+    /// Writes the current line, and has a blank line written ahead of the next block
+    fn end_block(&mut self) -> Result<(), std::fmt::Error> {
+        self.write()?;
+        self.blank_line_needed = true;
+        Ok(())
+    }
+
+    fn start_heading(&mut self) -> Result<(), std::fmt::Error> {
+        if self.options.link_style == PlaintextLinkStyle::SectionReference {
+            self.write_references()?;
+        }
+        self.in_heading = true;
+        self.start_block()
+    }
+
+    fn end_heading(&mut self, level: HeadingLevel) -> Result<(), std::fmt::Error> {
+        let width = self.write()?;
+        if self.options.heading_style == PlaintextHeadingStyle::Setext && width > 0 {
+            self.write_heading_underline(level, width)?;
+        }
+        self.in_heading = false;
+        self.blank_line_needed = true;
+        Ok(())
+    }
+
+    /// Closes an image opened with an `[Image: ` label
+    fn end_image_label(&mut self) {
+        if let Some(line) = self.current_line.strip_suffix("[Image: ") {
+            self.current_line = format!("{line}[Image]");
+        } else {
+            self.current_line.push(']');
+        }
+    }
+
+    fn start_container(&mut self, container: Container) -> Result<(), std::fmt::Error> {
+        self.start_block()?;
+        self.containers.push(container);
+        Ok(())
+    }
+
+    /// Closes a blockquote or list
+    fn end_container(&mut self) -> Result<(), std::fmt::Error> {
+        self.write()?;
+        self.containers.pop();
+        self.blank_line_needed = true;
+        Ok(())
+    }
+
+    fn start_item(&mut self) -> Result<(), std::fmt::Error> {
+        self.write()?;
+        let marker = match self.containers.last_mut() {
+            Some(Container::List {
+                next_number,
+                loose,
+                started,
+            }) => {
+                if *started {
+                    self.blank_line_needed = *loose;
+                }
+                *started = true;
+                match next_number {
+                    Some(number) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    None => String::from("- "),
+                }
+            }
+            _ => String::from("- "),
+        };
+        self.write_separator()?;
+        self.containers.push(Container::Item {
+            marker,
+            marker_pending: true,
+        });
+        Ok(())
+    }
+
+    fn end_item(&mut self) -> Result<(), std::fmt::Error> {
+        self.write()?;
+        if let Some(Container::Item {
+            marker_pending: true,
+            ..
+        }) = self.containers.last()
+        {
+            let prefix = self.line_prefix();
+            self.write_line(&prefix)?;
+        }
+        self.containers.pop();
+        Ok(())
+    }
+
+    /// Writes preformatted text line by line, with no wrapping
+    fn write_verbatim(&mut self, text: &str, indent: &str) -> Result<(), std::fmt::Error> {
+        for line in text.strip_suffix('\n').unwrap_or(text).split('\n') {
+            let prefix = self.line_prefix();
+            self.write_line(&format!("{prefix}{indent}{line}"))?;
+        }
+        Ok(())
+    }
+
+    fn write_rule(&mut self) -> Result<(), std::fmt::Error> {
+        self.start_block()?;
+        let prefix = self.line_prefix();
+        self.write_line(&format!("{prefix}---"))?;
+        self.blank_line_needed = true;
+        Ok(())
+    }
+}
+
+pub(super) fn push_plaintext<'a, I>(s: &mut String, iter: I, options: &'a ParseMarkdownOptions<'a>)
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    PlaintextWriter::new(iter, s, options).run().unwrap();
+}
+
+/// Writes the HTML fragment `html` as plaintext, with the same layout as Markdown input
+pub(super) fn push_html_plaintext<'a>(
+    s: &mut String,
+    html: &str,
+    options: &'a ParseMarkdownOptions<'a>,
+) {
+    let mut writer = PlaintextWriter::new(std::iter::empty(), s, options);
+    writer
+        .write_html(html)
+        .and_then(|()| writer.write_references())
+        .unwrap();
+}
@@ -0,0 +1,202 @@
+use html5ever::tendril::TendrilSink;
+use pulldown_cmark::{Event, HeadingLevel};
+use pulldown_cmark_escape::StrWrite;
+
+use super::{Container, PlaintextWriter};
+use crate::{
+    html_process::{
+        Builder,
+        dom::{Handle, NodeData},
+    },
+    markdown::HtmlElementRule,
+};
+
+/// Value of the `name` attribute of element `node`
+fn attribute(node: &Handle, name: &str) -> Option<String> {
+    let NodeData::Element { ref attrs, .. } = node.data else {
+        return None;
+    };
+    attrs
+        .borrow()
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| attr.value.to_string())
+}
+
+/// Text of `node` and its descendants, with whitespace kept
+fn text_content(node: &Handle) -> String {
+    match node.data {
+        NodeData::Text { ref contents } => contents.borrow().to_string(),
+        NodeData::Element { .. } => node.children.borrow().iter().map(text_content).collect(),
+        _ => String::new(),
+    }
+}
+
+fn heading_level(name: &str) -> Option<HeadingLevel> {
+    match name {
+        "h1" => Some(HeadingLevel::H1),
+        "h2" => Some(HeadingLevel::H2),
+        "h3" => Some(HeadingLevel::H3),
+        "h4" => Some(HeadingLevel::H4),
+        "h5" => Some(HeadingLevel::H5),
+        "h6" => Some(HeadingLevel::H6),
+        _ => None,
+    }
+}
+
+impl<'a, I, W> PlaintextWriter<'a, I, W>
+where
+    I: Iterator<Item = Event<'a>>,
+    W: StrWrite<Error = std::fmt::Error>,
+{
+    /// Writes an HTML fragment as plaintext, with the same layout as Markdown content
+    pub(super) fn write_html(&mut self, html: &str) -> Result<(), std::fmt::Error> {
+        let dom = Builder::make_parser().one(html);
+        let root = dom.document.children.borrow()[0].clone();
+        self.start_block()?;
+        self.write_html_children(&root)?;
+        self.end_block()
+    }
+
+    fn write_html_children(&mut self, node: &Handle) -> Result<(), std::fmt::Error> {
+        for child in node.children.borrow().iter() {
+            self.write_html_node(child)?;
+        }
+        Ok(())
+    }
+
+    /// Adds text with HTML whitespace collapsed to single spaces
+    fn push_html_text(&mut self, text: &str) {
+        let collapsed = text.split_ascii_whitespace().collect::<Vec<_>>().join(" ");
+        let at_line_start =
+            self.current_line.is_empty() || self.current_line.ends_with([' ', '\n']);
+        if text.starts_with(|c: char| c.is_ascii_whitespace()) && !at_line_start {
+            self.current_line.push(' ');
+        }
+        if collapsed.is_empty() {
+            return;
+        }
+        self.push_text(&collapsed);
+        if text.ends_with(|c: char| c.is_ascii_whitespace()) {
+            self.current_line.push(' ');
+        }
+    }
+
+    fn write_html_list(&mut self, node: &Handle, ordered: bool) -> Result<(), std::fmt::Error> {
+        let next_number = ordered.then(|| {
+            attribute(node, "start")
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(1)
+        });
+        self.start_container(Container::List {
+            next_number,
+            loose: false,
+            started: false,
+        })?;
+        self.write_html_children(node)?;
+        self.end_container()
+    }
+
+    fn push_html_image(&mut self, node: &Handle) {
+        let alt = attribute(node, "alt").unwrap_or_default();
+        if self.options.label_images {
+            self.current_line.push_str("[Image: ");
+            self.push_text(&alt);
+            self.end_image_label();
+        } else {
+            self.push_text(&alt);
+        }
+    }
+
+    fn write_html_node(&mut self, node: &Handle) -> Result<(), std::fmt::Error> {
+        let name = match node.data {
+            NodeData::Text { ref contents } => {
+                self.push_html_text(&contents.borrow());
+                return Ok(());
+            }
+            NodeData::Element { ref name, .. } => name.local.to_string(),
+            _ => return Ok(()),
+        };
+        match self.options.html_element_rule(&name) {
+            HtmlElementRule::Drop => return Ok(()),
+            HtmlElementRule::Attribute(attribute_name) => {
+                if let Some(value) = attribute(node, attribute_name) {
+                    self.push_text(&value);
+                    return Ok(());
+                }
+            }
+            HtmlElementRule::KeepText => {}
+        }
+        if let Some(level) = heading_level(&name) {
+            self.start_heading()?;
+            self.write_html_children(node)?;
+            return self.end_heading(level);
+        }
+        match name.as_str() {
+            "head" | "noscript" | "script" | "style" | "template" | "title" => {}
+            "br" => self.current_line.push('\n'),
+            "hr" => self.write_rule()?,
+            "p" => {
+                self.start_paragraph()?;
+                self.write_html_children(node)?;
+                self.end_block()?;
+            }
+            "summary" => {
+                // written as a label on its own line, directly above the details content
+                self.start_block()?;
+                self.write_html_children(node)?;
+                self.write()?;
+            }
+            "address" | "article" | "aside" | "caption" | "dd" | "details" | "div" | "dl"
+            | "dt" | "fieldset" | "figcaption" | "figure" | "footer" | "form" | "header"
+            | "main" | "nav" | "section" | "table" => {
+                self.start_block()?;
+                self.write_html_children(node)?;
+                self.end_block()?;
+            }
+            "tr" => {
+                self.write()?;
+                self.write_html_children(node)?;
+                self.write()?;
+            }
+            "td" | "th" => {
+                self.write_html_children(node)?;
+                self.current_line.push(' ');
+            }
+            "blockquote" => {
+                self.start_container(Container::BlockQuote)?;
+                self.write_html_children(node)?;
+                self.end_container()?;
+            }
+            "ol" | "ul" => self.write_html_list(node, name == "ol")?,
+            "li" => {
+                self.start_item()?;
+                self.write_html_children(node)?;
+                self.end_item()?;
+            }
+            "pre" => {
+                self.start_block()?;
+                let text = text_content(node);
+                self.write_verbatim(text.strip_prefix('\n').unwrap_or(&text), "    ")?;
+                self.blank_line_needed = true;
+            }
+            "code" | "kbd" | "samp" => self.push_code(&text_content(node)),
+            "a" => {
+                let text_start = self.current_line.len();
+                self.write_html_children(node)?;
+                if let Some(href) = attribute(node, "href") {
+                    self.write_link_destination(&href, text_start);
+                }
+            }
+            "img" => self.push_html_image(node),
+            "b" | "em" | "i" | "strong" => {
+                let marker = self.emphasis_marker(name == "b" || name == "strong");
+                self.current_line.push_str(&marker);
+                self.write_html_children(node)?;
+                self.current_line.push_str(&marker);
+            }
+            _ => self.write_html_children(node)?,
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,15 @@
-use crate::markdown::{
-    ParseMarkdownOptions, parse_markdown_to_html, parse_markdown_to_plaintext,
-    reading_time_from_words, slugified_title, words,
+use crate::{
+    markdown::{
+        CrossReferenceTemplates, Excerpt, Heading, HeadingOnePolicy, HtmlElementRule,
+        ParseMarkdownOptions, PlaintextEmphasisStyle, PlaintextHeadingStyle, PlaintextLinkStyle,
+        markdown_excerpt,
+        mdast::{HeadingData, Node, Point, Position},
+        parse_html_to_markdown, parse_html_to_plaintext, parse_markdown_to_html,
+        parse_markdown_to_markdown, parse_markdown_to_mdast, parse_markdown_to_plaintext,
+        reading_time_from_words, slugified_title, words,
+    },
+    text::Locale,
+    url_utility::{QueryParameterRule, UrlRewriter},
 };
 
 #[test]
@@ -47,7 +56,9 @@ hello
 * beta
 ";
 
-    let Ok((result, _headings, _statistics)) = parse_markdown_to_html(markdown) else {
+    let Ok((result, _headings, _statistics, _cross_references)) =
+        parse_markdown_to_html(markdown, &ParseMarkdownOptions::default())
+    else {
         panic!("Result expected");
     };
 
@@ -114,6 +125,329 @@ pub fn parse_markdown_to_plaintext_outputs_relative_urls_when_canonical_root_url
     assert_eq!(result, expected);
 }
 
+#[test]
+pub fn parse_markdown_to_plaintext_wraps_to_line_length() {
+    let markdown = "Run `cargo test --workspace` then see https://example.com/a-very-long-path.";
+
+    let mut options = ParseMarkdownOptions::default();
+    options.line_length(Some(20));
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "Run\ncargo test --workspace\nthen see\nhttps://example.com/a-very-long-path.\n",
+    );
+    assert_eq!(result, expected);
+
+    options.line_length(None);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected =
+        String::from("Run cargo test --workspace then see https://example.com/a-very-long-path.\n");
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_indents_wrapped_list_items() {
+    let markdown = "- first item wraps onto a second line\n- second\n";
+
+    let mut options = ParseMarkdownOptions::default();
+    options.line_length(Some(20));
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    assert_eq!(
+        result,
+        "- first item wraps\n  onto a second line\n- second\n"
+    );
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_renders_all_block_types() {
+    let markdown = r"## Steps
+
+3. Install
+4. Configure:
+   - edit `config.toml`
+   - restart
+
+> Quoted text,  
+> with a hard break.
+>
+> Second quoted paragraph.
+
+```rust
+fn main() {
+
+    println!();
+}
+```
+
+---
+
+    indented code
+";
+
+    let result = parse_markdown_to_plaintext(markdown, &ParseMarkdownOptions::default());
+    let expected = String::from(
+        "Steps
+
+3. Install
+4. Configure:
+   - edit config.toml
+   - restart
+
+> Quoted text,
+> with a hard break.
+>
+> Second quoted paragraph.
+
+```rust
+fn main() {
+
+    println!();
+}
+```
+
+---
+
+    indented code
+",
+    );
+    assert_eq!(result, expected);
+
+    let markdown = "- one\n\n- two\n\nAfter the list.\n";
+    let result = parse_markdown_to_plaintext(markdown, &ParseMarkdownOptions::default());
+    assert_eq!(result, "- one\n\n- two\n\nAfter the list.\n");
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_lists_reference_links() {
+    let markdown = r"## Intro
+
+Read the [guide](/guide), then the [FAQ](https://example.com/faq) and the
+[guide again](/guide). [Jump ahead](#setup) or email
+[support](mailto:help@example.com) or <https://example.com/faq>.
+
+## Setup
+
+See the [FAQ](https://example.com/faq).
+";
+
+    let mut options = ParseMarkdownOptions::default();
+    options
+        .canonical_root_url(Some("https://example.com"))
+        .link_style(PlaintextLinkStyle::Reference);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "Intro
+
+Read the guide [1], then the FAQ [2] and the guide again [1]. Jump ahead
+or email support [3] or https://example.com/faq.
+
+Setup
+
+See the FAQ [2].
+
+[1] https://example.com/guide
+[2] https://example.com/faq
+[3] help@example.com
+",
+    );
+    assert_eq!(result, expected);
+
+    options.link_style(PlaintextLinkStyle::SectionReference);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "Intro
+
+Read the guide [1], then the FAQ [2] and the guide again [1]. Jump ahead
+or email support [3] or https://example.com/faq.
+
+[1] https://example.com/guide
+[2] https://example.com/faq
+[3] help@example.com
+
+Setup
+
+See the FAQ [1].
+
+[1] https://example.com/faq
+",
+    );
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_styles_headings_emphasis_and_images() {
+    let markdown = r"# Release notes
+
+## What's *new*
+
+Some *emphasis*, **strong** text and ![Chart of downloads](/chart.png) plus ![](/logo.png).
+";
+
+    let mut options = ParseMarkdownOptions::default();
+    options
+        .heading_style(PlaintextHeadingStyle::Setext)
+        .emphasis_style(PlaintextEmphasisStyle::Underscore)
+        .label_images(true);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "Release notes
+=============
+
+What\u{2019}s _new_
+------------
+
+Some _emphasis_, __strong__ text and [Image: Chart of downloads] plus
+[Image].
+",
+    );
+    assert_eq!(result, expected);
+
+    options
+        .heading_style(PlaintextHeadingStyle::Uppercase)
+        .emphasis_style(PlaintextEmphasisStyle::Asterisk)
+        .label_images(false);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "RELEASE NOTES
+
+WHAT\u{2019}S *NEW*
+
+Some *emphasis*, **strong** text and Chart of downloads plus .
+",
+    );
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_applies_html_element_rules() {
+    let markdown = r#"Press <kbd>Ctrl</kbd> to run the <abbr title="Command Line Interface">CLI</abbr><sup>1</sup>."#;
+
+    let result = parse_markdown_to_plaintext(markdown, &ParseMarkdownOptions::default());
+    assert_eq!(result, "Press Ctrl to run the CLI1.\n");
+
+    let mut options = ParseMarkdownOptions::default();
+    options.html_element_rules(vec![
+        ("abbr", HtmlElementRule::Attribute("title")),
+        ("sup", HtmlElementRule::Drop),
+    ]);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    assert_eq!(result, "Press Ctrl to run the Command Line Interface.\n");
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_applies_html_element_rules_to_void_elements() {
+    let mut options = ParseMarkdownOptions::default();
+    options.html_element_rules(vec![("img", HtmlElementRule::Attribute("alt"))]);
+    let result = parse_markdown_to_plaintext(
+        "Icon <img src=\"/a.png\" alt=\"Logo\"> here.\n\nSecond paragraph.\n",
+        &options,
+    );
+    assert_eq!(result, "Icon Logo here.\n\nSecond paragraph.\n");
+
+    let mut options = ParseMarkdownOptions::default();
+    options.html_element_rules(vec![("br", HtmlElementRule::Drop)]);
+    let result = parse_markdown_to_plaintext("One<br>two\n\nThree\n", &options);
+    assert_eq!(result, "Onetwo\n\nThree\n");
+
+    let result = parse_markdown_to_plaintext("One<br />two\n\nThree\n", &options);
+    assert_eq!(result, "Onetwo\n\nThree\n");
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_stops_skipping_unclosed_elements_at_block_end() {
+    let mut options = ParseMarkdownOptions::default();
+    options.html_element_rules(vec![("span", HtmlElementRule::Drop)]);
+    let result =
+        parse_markdown_to_plaintext("Kept <span>dropped *text*\n\nNext paragraph.\n", &options);
+    assert_eq!(result, "Kept\n\nNext paragraph.\n");
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_converts_block_html() {
+    let markdown = r#"Before the details.
+
+<details>
+<summary>Show <em>more</em></summary>
+Hidden <a href="/notes">notes</a>.<br>Second line.
+<div class="advert">Buy now!</div>
+</details>
+
+<figure>
+  <img src="/chart.png" alt="Downloads chart">
+  <figcaption>Downloads by month</figcaption>
+</figure>
+
+After the figure.
+"#;
+
+    let mut options = ParseMarkdownOptions::default();
+    options
+        .canonical_root_url(Some("https://example.com"))
+        .label_images(true)
+        .html_element_rules(vec![("div", HtmlElementRule::Drop)]);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "Before the details.
+
+Show more
+Hidden notes (https://example.com/notes).
+Second line.
+
+[Image: Downloads chart]
+Downloads by month
+
+After the figure.
+",
+    );
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn parse_html_to_plaintext_matches_markdown_output() {
+    let markdown = r"## Getting started
+
+Read the [installation guide](/docs/install) before you begin, as there are a few system
+requirements to check first.
+
+1. Download
+2. Install:
+   - run `npm install`
+   - restart
+
+> Quoted text.
+";
+    let html = r#"<h2>Getting started</h2>
+<p>Read the <a href="/docs/install">installation guide</a> before you begin, as there are a few
+system requirements to check first.</p>
+<ol>
+  <li>Download</li>
+  <li>Install:
+    <ul><li>run <code>npm install</code></li><li>restart</li></ul>
+  </li>
+</ol>
+<blockquote><p>Quoted text.</p></blockquote>
+<script>alert("hidden");</script>"#;
+
+    let mut options = ParseMarkdownOptions::default();
+    options.canonical_root_url(Some("https://example.com"));
+    let result = parse_html_to_plaintext(html, &options);
+    assert_eq!(result, parse_markdown_to_plaintext(markdown, &options));
+    assert_eq!(
+        result,
+        "Getting started
+
+Read the installation guide (https://example.com/docs/install) before
+you begin, as there are a few system requirements to check first.
+
+1. Download
+2. Install:
+   - run npm install
+   - restart
+
+> Quoted text.
+"
+    );
+}
+
 #[test]
 pub fn test_slugified_title() {
     let title = "Heading One";
@@ -128,3 +462,438 @@ pub fn test_slugified_title() {
     let title = "Heading Four!";
     assert_eq!(slugified_title(title), "heading-four");
 }
+
+#[test]
+pub fn parse_markdown_to_plaintext_rewrites_link_query_parameters() {
+    let markdown = "[Contact us](/contact?fbclid=abc) to find out more.";
+
+    let mut url_rewriter = UrlRewriter::default();
+    url_rewriter
+        .append_parameters(vec![QueryParameterRule::new(
+            "example.com",
+            vec![("utm_campaign", "spring")],
+        )])
+        .strip_parameters(vec!["fbclid"]);
+    let mut options = ParseMarkdownOptions::default();
+    options
+        .canonical_root_url(Some("https://example.com"))
+        .url_rewriter(url_rewriter);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from(
+        "Contact us (https://example.com/contact?utm_campaign=spring) to find\nout more.\n",
+    );
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_applies_locale_typography() {
+    let markdown = r#"Il a dit "oui" : c'est vrai !"#;
+
+    let mut options = ParseMarkdownOptions::default();
+    options.locale(Some(Locale::French));
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    let expected = String::from("Il a dit «\u{202f}oui\u{202f}»\u{202f}: c’est vrai\u{202f}!\n");
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn parse_markdown_to_html_drops_emoji_from_slugs() {
+    let markdown = "## Hello 👋🏽\n\n## Hello :wave:\n";
+
+    let options = ParseMarkdownOptions::default();
+    let (_html, headings, _statistics, _cross_references) =
+        parse_markdown_to_html(markdown, &options).unwrap();
+    assert_eq!(headings[0].id(), "hello-wave-");
+
+    let mut options = ParseMarkdownOptions::default();
+    options
+        .enable_emoji_shortcodes(true)
+        .drop_emoji_from_slugs(true);
+    let (_html, headings, _statistics, _cross_references) =
+        parse_markdown_to_html(markdown, &options).unwrap();
+    assert_eq!(headings[0].id(), "hello");
+    assert_eq!(headings[1].id(), "hello");
+}
+
+#[test]
+pub fn parse_markdown_to_plaintext_expands_emoji_shortcodes() {
+    let markdown = "Ship it :ship:\n\n```\n:ship:\n```\n";
+
+    let mut options = ParseMarkdownOptions::default();
+    options.enable_emoji_shortcodes(true);
+    let result = parse_markdown_to_plaintext(markdown, &options);
+    assert!(result.starts_with("Ship it 🚢\n"));
+}
+
+#[test]
+pub fn parse_markdown_to_html_keeps_heading_attributes() {
+    let markdown = "## Getting Started {#setup .wide data-section=intro}\n\n### Next steps\n";
+
+    let (html, headings, _statistics, _cross_references) =
+        parse_markdown_to_html(markdown, &ParseMarkdownOptions::default()).unwrap();
+    assert_eq!(
+        html,
+        "<h2 id=\"setup\" class=\"wide\" data-section=\"intro\">Getting Started</h2>\n<h3 id=\"next-steps\">Next steps</h3>\n"
+    );
+    assert_eq!(
+        headings,
+        vec![
+            Heading::new("Getting Started", "setup", 2),
+            Heading::new("Next steps", "next-steps", 3)
+        ]
+    );
+}
+
+#[test]
+pub fn parse_markdown_to_html_parses_gfm_only_when_enabled() {
+    let markdown = "| a | b |\n| - | - |\n| 1 | 2 |\n\nSome ~~old~~ text\n";
+
+    let (html, ..) = parse_markdown_to_html(markdown, &ParseMarkdownOptions::default()).unwrap();
+    assert_eq!(
+        html,
+        "<p>| a | b |\n| - | - |\n| 1 | 2 |</p>\n<p>Some ~~old~~ text</p>\n"
+    );
+
+    let mut options = ParseMarkdownOptions::default();
+    options.enable_gfm(true);
+    let (html, ..) = parse_markdown_to_html(markdown, &options).unwrap();
+    assert_eq!(
+        html,
+        "<table><thead><tr><th>a</th><th>b</th></tr></thead><tbody>\n<tr><td>1</td><td>2</td></tr>\n</tbody></table>\n<p>Some <del>old</del> text</p>\n"
+    );
+}
+
+#[test]
+pub fn parse_markdown_to_html_demotes_body_heading_one() {
+    let markdown = "# Title\n\n## Section\n";
+
+    let mut options = ParseMarkdownOptions::default();
+    options.heading_one_policy(HeadingOnePolicy::Demote);
+    let (html, headings, _statistics, _cross_references) =
+        parse_markdown_to_html(markdown, &options).unwrap();
+    assert_eq!(
+        html,
+        "<h2 id=\"title\">Title</h2>\n<h2 id=\"section\">Section</h2>\n"
+    );
+    assert_eq!(
+        headings,
+        vec![
+            Heading::new("Title", "title", 2),
+            Heading::new("Section", "section", 2)
+        ]
+    );
+}
+
+#[test]
+pub fn markdown_excerpt_stops_at_more_marker() {
+    let markdown = "# Title\n\nFirst *paragraph* here.\n\n```js\nlet x;\n```\n\n<!-- more -->\n\nLater text.\n";
+
+    let excerpt = markdown_excerpt(markdown, &ParseMarkdownOptions::default());
+    assert_eq!(
+        excerpt,
+        Excerpt::new(
+            "<p>First <em>paragraph</em> here.</p>\n",
+            "First paragraph here.",
+            "First paragraph here."
+        )
+    );
+}
+
+#[test]
+pub fn markdown_excerpt_parses_like_the_document_body() {
+    let markdown = "Some ~~old~~ news about [[Release Notes]].\n\n<!-- more -->\n\nLater text.\n";
+
+    let mut options = ParseMarkdownOptions::default();
+    options
+        .enable_gfm(true)
+        .cross_reference_templates(CrossReferenceTemplates {
+            wiki_link: Some("/wiki/{slug}"),
+            mention: None,
+            hashtag: None,
+        });
+    let (html, ..) = parse_markdown_to_html(markdown, &options).unwrap();
+    assert!(html.starts_with(
+        "<p>Some <del>old</del> news about <a href=\"/wiki/release-notes\" class=\"wiki-link\">Release Notes</a>.</p>\n"
+    ));
+
+    let excerpt = markdown_excerpt(markdown, &options);
+    assert_eq!(
+        excerpt,
+        Excerpt::new(
+            "<p>Some <del>old</del> news about <a href=\"/wiki/release-notes\" class=\"wiki-link\">Release Notes</a>.</p>\n",
+            "Some old news about Release Notes.",
+            "Some old news about Release Notes."
+        )
+    );
+}
+
+#[test]
+pub fn markdown_excerpt_truncates_at_sentence_or_word_limit() {
+    let markdown = "One two three. Four five six seven.\n";
+
+    let mut options = ParseMarkdownOptions::default();
+    options.excerpt_words(5).description_length(13);
+    let excerpt = markdown_excerpt(markdown, &options);
+    assert_eq!(
+        excerpt,
+        Excerpt::new(
+            "<p>One two three.</p>\n",
+            "One two three.",
+            "One two\u{2026}"
+        )
+    );
+
+    let markdown = "One two *three four* five six seven.\n";
+    let excerpt = markdown_excerpt(markdown, &options);
+    assert_eq!(
+        excerpt,
+        Excerpt::new(
+            "<p>One two <em>three four</em> five\u{2026}</p>\n",
+            "One two three four five\u{2026}",
+            "One two\u{2026}"
+        )
+    );
+}
+
+#[test]
+pub fn parse_html_to_markdown_converts_common_elements() {
+    let html = r#"<h2 id="intro">Getting <em>started</em></h2>
+<p>Read the <a href="/docs/install" title="Guide">installation guide</a> and run
+<code>npm install</code>.  Prices start at *5 &amp; rise.<br>Next line</p>
+<ul>
+  <li>Download</li>
+  <li>Install:
+    <ol><li><strong>run</strong> it</li><li><del>restart</del></li></ol>
+  </li>
+</ul>
+<ul><li>Second list</li></ul>
+<blockquote><p>Quoted <abbr title="HyperText">HTML</abbr> text.</p><p>1. Not a list</p></blockquote>
+<pre><code class="language-rust">fn main() {}
+</code></pre>
+<table><thead><tr><th>Name</th><th align="right">Size</th></tr></thead>
+<tbody><tr><td>a|b</td><td>10</td></tr></tbody></table>
+<figure><img src="/dog.png" alt="Dog"><figcaption>A dog</figcaption></figure>"#;
+
+    assert_eq!(
+        parse_html_to_markdown(html),
+        r#"## Getting *started* {#intro}
+
+Read the [installation guide](/docs/install "Guide") and run `npm install`. Prices start at \*5 & rise.\
+Next line
+
+- Download
+- Install:
+  1. **run** it
+  2. ~~restart~~
+
+* Second list
+
+> Quoted <abbr title="HyperText">HTML</abbr> text.
+>
+> 1\. Not a list
+
+```rust
+fn main() {}
+```
+
+| Name | Size |
+| ---- | ---: |
+| a\|b |   10 |
+
+<figure><img src="/dog.png" alt="Dog"><figcaption>A dog</figcaption></figure>
+"#
+    );
+}
+
+#[test]
+pub fn parse_html_to_markdown_round_trips_markdown_to_html_output() {
+    let markdown = r#"# Release notes
+
+Install the [CLI](https://example.com/cli "Command line") with `cargo install`, then read the *guide* and **changelog**.
+
+1. Download
+2. Configure:
+   - edit `config.toml`
+   - restart
+
+> Deprecated ~~flags~~ are removed.
+
+![Diagram](/images/diagram.png)
+
+| Option | Default |
+| :----- | :-----: |
+| `port` |  8080   |
+
+---
+"#;
+    let mut options = ParseMarkdownOptions::default();
+    options.enable_gfm(true);
+    let (html, ..) = parse_markdown_to_html(markdown, &options).unwrap();
+    let result = parse_html_to_markdown(&html);
+    let (round_trip_html, ..) = parse_markdown_to_html(&result, &options).unwrap();
+    assert_eq!(round_trip_html, html);
+}
+
+#[test]
+pub fn parse_markdown_to_markdown_normalises_style() {
+    let markdown = r#"Setext heading
+==============
+
+* first item with a long line that keeps going past the wrapping width
+* second [item][Docs] and [docs] with `code that has spaces`
+
++ adjacent list
+
+Text with a [link](https://example.com/a(b) "T") and <span class="x y">inline html</span> plus
+a hard break  
+here.
+
+[Docs]: https://example.com/docs "Documentation"
+[unused]: /unused
+
+    indented code
+
+|a|b|
+|-|:-:|
+|long cell|x|
+
+> quote with *emphasis* and a few more words to wrap
+"#;
+    let mut options = ParseMarkdownOptions::default();
+    options.line_length(Some(40)).enable_gfm(true);
+    let result = parse_markdown_to_markdown(markdown, &options);
+    assert_eq!(
+        result,
+        r#"# Setext heading
+
+- first item with a long line that keeps
+  going past the wrapping width
+- second [item][Docs] and [docs] with
+  `code that has spaces`
+
+* adjacent list
+
+Text with a
+[link](https://example.com/a(b) "T")
+and <span class="x y">inline html</span>
+plus a hard break\
+here.
+
+```
+indented code
+```
+
+| a         |  b  |
+| --------- | :-: |
+| long cell |  x  |
+
+> quote with *emphasis* and a few more
+> words to wrap
+
+[Docs]: https://example.com/docs "Documentation"
+[unused]: /unused
+"#
+    );
+    assert_eq!(parse_markdown_to_markdown(&result, &options), result);
+}
+
+#[test]
+pub fn parse_markdown_to_markdown_keeps_line_breaks_without_line_length() {
+    let markdown = "Some *text*,\nwith a line break and \"quotes\" :smile:\n";
+    let mut options = ParseMarkdownOptions::default();
+    options.line_length(None).enable_emoji_shortcodes(true);
+    assert_eq!(parse_markdown_to_markdown(markdown, &options), markdown);
+}
+
+#[test]
+pub fn parse_markdown_to_mdast_includes_positions_and_heading_ids() {
+    let markdown = "## Hi {#hello}\n\nOne 👋 *two*\n";
+    let position = |start: (usize, usize, usize), end: (usize, usize, usize)| {
+        Position::new(
+            Point::new(start.0, start.1, start.2),
+            Point::new(end.0, end.1, end.2),
+        )
+    };
+    let expected = Node::Root {
+        children: vec![
+            Node::Heading {
+                depth: 2,
+                children: vec![Node::Text {
+                    value: String::from("Hi"),
+                    position: position((1, 4, 3), (1, 6, 5)),
+                }],
+                data: HeadingData::new("hello"),
+                position: position((1, 1, 0), (1, 15, 14)),
+            },
+            Node::Paragraph {
+                children: vec![
+                    Node::Text {
+                        value: String::from("One 👋 "),
+                        position: position((3, 1, 16), (3, 8, 23)),
+                    },
+                    Node::Emphasis {
+                        children: vec![Node::Text {
+                            value: String::from("two"),
+                            position: position((3, 9, 24), (3, 12, 27)),
+                        }],
+                        position: position((3, 8, 23), (3, 13, 28)),
+                    },
+                ],
+                position: position((3, 1, 16), (3, 13, 28)),
+            },
+        ],
+        position: position((1, 1, 0), (4, 1, 29)),
+    };
+    assert_eq!(
+        parse_markdown_to_mdast(markdown, &ParseMarkdownOptions::default()),
+        expected
+    );
+
+    // generated ids match the HTML output
+    let markdown = "# Getting started\n\n1. [docs](/docs \"Docs\")\n";
+    let Node::Root { children, .. } =
+        parse_markdown_to_mdast(markdown, &ParseMarkdownOptions::default())
+    else {
+        panic!("Expected a root node");
+    };
+    let [Node::Heading { data, .. }, Node::List { children, .. }] = &children[..] else {
+        panic!("Expected a heading and a list");
+    };
+    assert_eq!(*data, HeadingData::new("getting-started"));
+    let [Node::ListItem { children, .. }] = &children[..] else {
+        panic!("Expected a single list item");
+    };
+    assert!(matches!(
+        &children[..],
+        [Node::Link { url, title: Some(title), .. }] if url == "/docs" && title == "Docs"
+    ));
+}
+
+#[test]
+pub fn parse_markdown_to_html_does_not_keep_first_pass_output() {
+    let markdown = "[[A B C D E F G H I J K L M N O P Q R S T U V W X Y Z]]\n";
+    let mut options = ParseMarkdownOptions::default();
+    options.cross_reference_templates(CrossReferenceTemplates {
+        wiki_link: Some("{slug}"),
+        mention: None,
+        hashtag: None,
+    });
+    let (html, ..) = parse_markdown_to_html(markdown, &options).unwrap();
+    assert_eq!(
+        html,
+        "<p><a href=\"a-b-c-d-e-f-g-h-i-j-k-l-m-n-o-p-q-r-s-t-u-v-w-x-y-z\" class=\"wiki-link\">A B C D E F G H I J K L M N O P Q R S T U V W X Y Z</a></p>\n"
+    );
+}
+
+#[test]
+pub fn parse_markdown_to_markdown_escapes_pipes_in_table_code_spans() {
+    let markdown = "| Operator | Meaning |\n| -------- | ------- |\n| `x\\|y`   | or      |\n";
+    let mut options = ParseMarkdownOptions::default();
+    options.enable_gfm(true);
+    let result = parse_markdown_to_markdown(markdown, &options);
+    assert_eq!(result, markdown);
+    assert_eq!(
+        parse_markdown_to_html(&result, &options).unwrap().0,
+        parse_markdown_to_html(markdown, &options).unwrap().0
+    );
+}
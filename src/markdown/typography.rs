@@ -0,0 +1,101 @@
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+
+use crate::text::{Locale, typeset};
+
+/// Adaptor over Markdown events applying locale typography to text outside code.  With no locale,
+/// events pass through unchanged.
+pub struct Typography<I> {
+    iter: I,
+    locale: Option<Locale>,
+
+    /// Last character of preceding text in the current block, so quotes spanning inline markup
+    /// open and close correctly
+    previous: Option<char>,
+    in_code_block: bool,
+}
+
+impl<'a, I> Typography<I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    pub fn new(iter: I, locale: Option<Locale>) -> Self {
+        Self {
+            iter,
+            locale,
+            previous: None,
+            in_code_block: false,
+        }
+    }
+}
+
+impl<'a, I> Iterator for Typography<I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.iter.next()?;
+        let Some(locale) = self.locale else {
+            return Some(event);
+        };
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.in_code_block = true;
+                Some(event)
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                self.in_code_block = false;
+                Some(event)
+            }
+            Event::Start(ref tag) if !is_inline(tag) => {
+                self.previous = None;
+                Some(event)
+            }
+            Event::End(tag) if !is_inline_end(tag) => {
+                self.previous = None;
+                Some(event)
+            }
+            Event::Text(text) if !self.in_code_block => {
+                let result = typeset(&text, locale, self.previous);
+                self.previous = text.chars().next_back().or(self.previous);
+                Some(Event::Text(CowStr::from(result)))
+            }
+            Event::Code(ref text) => {
+                self.previous = text.chars().next_back().or(self.previous);
+                Some(event)
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                self.previous = Some(' ');
+                Some(event)
+            }
+            _ => Some(event),
+        }
+    }
+}
+
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+fn is_inline_end(tag: TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
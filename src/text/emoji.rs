@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use emojis::Emoji;
+
+/// Longest emoji sequence, in characters, checked for when scanning text
+const MAX_EMOJI_LENGTH: usize = 10;
+
+/// Emoji found in text, with its byte range
+#[derive(Debug)]
+pub struct EmojiMatch {
+    pub start: usize,
+    pub end: usize,
+    pub emoji: &'static Emoji,
+}
+
+/// `true` for emoji usually shown as pictures.  Symbols like `©` and `™` are also in the emoji
+/// data but display as text unless followed by a variation selector.
+fn emoji_presentation(value: &str) -> bool {
+    value.chars().any(|c| {
+        c == '\u{fe0f}'
+            || c >= '\u{1f000}'
+            || ('\u{2600}'..='\u{27bf}').contains(&c)
+            || ('\u{2b00}'..='\u{2bff}').contains(&c)
+    })
+}
+
+/// Emoji, including skin tone and joined sequences, in `text`
+pub fn find_emoji(text: &str) -> Vec<EmojiMatch> {
+    let mut result = Vec::new();
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([text.len()])
+        .collect();
+    let mut position = 0;
+    while position + 1 < boundaries.len() {
+        let start = boundaries[position];
+        let longest = (position + 1..boundaries.len().min(position + MAX_EMOJI_LENGTH + 1))
+            .rev()
+            .find_map(|end_position| {
+                let value = &text[start..boundaries[end_position]];
+                emojis::get(value)
+                    .filter(|_| emoji_presentation(value))
+                    .map(|emoji| (end_position, emoji))
+            });
+        if let Some((end_position, emoji)) = longest {
+            result.push(EmojiMatch {
+                start,
+                end: boundaries[end_position],
+                emoji,
+            });
+            position = end_position;
+        } else {
+            position += 1;
+        }
+    }
+    result
+}
+
+/// `text` with emoji removed
+pub fn remove_emoji(text: &str) -> Cow<'_, str> {
+    let matches = find_emoji(text);
+    if matches.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut index = 0;
+    for emoji_match in matches {
+        result.push_str(&text[index..emoji_match.start]);
+        index = emoji_match.end;
+    }
+    result.push_str(&text[index..]);
+    Cow::Owned(result)
+}
+
+/// Replaces GitHub-style shortcodes, like `:rocket:`, with their emoji.  Unknown shortcodes are
+/// left unchanged.
+pub fn expand_shortcodes(text: &str) -> Cow<'_, str> {
+    if !text.contains(':') {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+    while let Some(colon) = rest.find(':') {
+        let before = &rest[..colon];
+        result.push_str(before);
+        let previous_character = before.chars().next_back().or(previous);
+        let after = &rest[colon + 1..];
+        let shortcode_end =
+            after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '+' || c == '-'));
+        let emoji = match shortcode_end {
+            Some(end) if end > 0 && after[end..].starts_with(':') => previous_character
+                .is_none_or(|c| !c.is_alphanumeric())
+                .then(|| emojis::get_by_shortcode(&after[..end]))
+                .flatten()
+                .map(|emoji| (end, emoji)),
+            _ => None,
+        };
+        if let Some((end, emoji)) = emoji {
+            result.push_str(emoji.as_str());
+            previous = emoji.as_str().chars().next_back();
+            rest = &after[end + 1..];
+        } else {
+            result.push(':');
+            previous = Some(':');
+            rest = after;
+        }
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
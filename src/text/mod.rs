@@ -1,66 +1,274 @@
 #[cfg(test)]
 mod tests;
 
-use std::borrow::Cow;
+mod emoji;
 
-pub fn format_line<'a, I: Into<Cow<'a, str>>>(line: I) -> Cow<'a, str> {
-    let line = line.into();
-    fn is_replace_character(c: char) -> bool {
-        c == '\'' || c == '"'
+pub use emoji::{expand_shortcodes, find_emoji, remove_emoji};
+
+/// Typographic conventions for quotation marks and punctuation spacing
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Locale {
+    /// “…” and ‘…’
+    English,
+
+    /// „…“ and ‚…‘
+    German,
+
+    /// « … » with narrow no-break spaces, also before `;`, `:`, `!` and `?`
+    French,
+
+    /// «…» and ‹…›, also used for Norwegian
+    Swiss,
+
+    /// ”…” and ’…’, also used for Finnish
+    Swedish,
+
+    /// »…« and ›…‹
+    Danish,
+
+    /// „…“ and ‚…‘, also used for Slovak
+    Czech,
+
+    /// „…” and «…»
+    Polish,
+}
+
+/// Narrow no-break space, used in French punctuation spacing
+const NARROW_NO_BREAK_SPACE: char = '\u{202f}';
+
+struct QuoteStyle {
+    double_open: &'static str,
+    double_close: &'static str,
+    single_open: &'static str,
+    single_close: &'static str,
+}
+
+const ENGLISH_QUOTES: QuoteStyle = QuoteStyle {
+    double_open: "\u{201c}",
+    double_close: "\u{201d}",
+    single_open: "\u{2018}",
+    single_close: "\u{2019}",
+};
+
+impl Locale {
+    /// Locale for a BCP 47 language tag, like `de`, `fr-FR` or `de-CH`, defaults to `English`
+    pub fn from_language_tag(tag: &str) -> Locale {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        let mut subtags = tag.split('-');
+        let language = subtags.next().unwrap_or_default();
+        let region = subtags.next().unwrap_or_default();
+        match (language, region) {
+            ("de" | "fr" | "it" | "rm", "ch" | "li") | ("nb" | "nn" | "no", _) => Locale::Swiss,
+            ("de", _) => Locale::German,
+            ("fr", _) => Locale::French,
+            ("sv" | "fi", _) => Locale::Swedish,
+            ("da", _) => Locale::Danish,
+            ("cs" | "sk", _) => Locale::Czech,
+            ("pl", _) => Locale::Polish,
+            _ => Locale::English,
+        }
     }
 
-    let first = line.find(is_replace_character);
-    if let Some(value) = first {
-        let (mut result, rest) = match value {
-            0 => match &line[0..1] {
-                "\"" => (String::from('\u{201c}'), line[1..].chars()),
-                "'" => (String::from('\u{2018}'), line[1..].chars()),
-                _ => (String::from(&line[0..value]), line[value..].chars()),
+    fn quote_style(self) -> QuoteStyle {
+        match self {
+            Locale::English => ENGLISH_QUOTES,
+            Locale::German | Locale::Czech => QuoteStyle {
+                double_open: "\u{201e}",
+                double_close: "\u{201c}",
+                single_open: "\u{201a}",
+                single_close: "\u{2018}",
+            },
+            Locale::French => QuoteStyle {
+                double_open: "\u{ab}\u{202f}",
+                double_close: "\u{202f}\u{bb}",
+                single_open: "\u{201c}",
+                single_close: "\u{201d}",
+            },
+            Locale::Swiss => QuoteStyle {
+                double_open: "\u{ab}",
+                double_close: "\u{bb}",
+                single_open: "\u{2039}",
+                single_close: "\u{203a}",
+            },
+            Locale::Swedish => QuoteStyle {
+                double_open: "\u{201d}",
+                double_close: "\u{201d}",
+                single_open: "\u{2019}",
+                single_close: "\u{2019}",
             },
-            _ => {
-                if &line[(value - 1)..value] == " " {
-                    (
-                        String::from(&line[0..(value - 1)]),
-                        line[(value - 1)..].chars(),
-                    )
+            Locale::Danish => QuoteStyle {
+                double_open: "\u{bb}",
+                double_close: "\u{ab}",
+                single_open: "\u{203a}",
+                single_close: "\u{2039}",
+            },
+            Locale::Polish => QuoteStyle {
+                double_open: "\u{201e}",
+                double_close: "\u{201d}",
+                single_open: "\u{ab}",
+                single_close: "\u{bb}",
+            },
+        }
+    }
+
+    /// `true` for locales where single-letter words, such as prepositions, should not end a line
+    pub fn joins_single_letter_words(self) -> bool {
+        matches!(self, Locale::Czech | Locale::Polish)
+    }
+}
+
+/// A quote after one of these characters, or at the start of a line, opens a quotation
+fn opens_quotation(previous: char) -> bool {
+    previous.is_whitespace() || "([{\u{2013}\u{2014}".contains(previous)
+}
+
+/// Replaces straight quotes with `style` quotes.  `previous` is the character before `line`, if
+/// it continues earlier text.
+fn convert_quotes(line: &str, style: &QuoteStyle, previous: Option<char>) -> String {
+    let mut result = String::with_capacity(line.len() + 8);
+    let mut previous = previous;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let opening = previous.is_none_or(opens_quotation);
+        match c {
+            '\'' if opening => result.push_str(style.single_open),
+            '\'' => {
+                // apostrophes within words are always `’`
+                if chars.peek().is_some_and(|next| next.is_alphabetic()) {
+                    result.push('\u{2019}');
                 } else {
-                    (String::from(&line[0..value]), line[value..].chars())
+                    result.push_str(style.single_close);
                 }
             }
-        };
-        result.reserve(line.len() - value);
-
-        let mut preceded_by_space = false;
-        for c in rest {
-            match c {
-                '\'' => {
-                    if preceded_by_space {
-                        preceded_by_space = false;
-                        result.push('\u{2018}')
-                    } else {
-                        result.push('\u{2019}')
-                    }
-                }
-                '"' => {
-                    if preceded_by_space {
-                        preceded_by_space = false;
-                        result.push('\u{201c}')
-                    } else {
-                        result.push('\u{201d}')
-                    }
-                }
-                ' ' => {
-                    preceded_by_space = true;
-                    result.push(c);
-                }
-                _ => {
-                    preceded_by_space = false;
-                    result.push(c);
-                }
+            '"' if opening => result.push_str(style.double_open),
+            '"' => result.push_str(style.double_close),
+            _ => result.push(c),
+        }
+        previous = Some(c);
+    }
+    result
+}
+
+/// Replaces `---` with an em dash, `--` with an en dash and `...` with an ellipsis
+fn dashes_and_ellipses(line: &str) -> String {
+    line.replace("---", "\u{2014}")
+        .replace("--", "\u{2013}")
+        .replace("...", "\u{2026}")
+}
+
+/// Whether `start-end` reads as a range, like `10-20`, `9-12` or `2024-25`, rather than a phone,
+/// part or postal code, like `555-1234` or `0800-123`
+fn plausible_range(start: &str, end: &str) -> bool {
+    let leading_zero = |value: &str| value.len() > 1 && value.starts_with('0');
+    if leading_zero(start) || leading_zero(end) {
+        return false;
+    }
+    let (Ok(start_value), Ok(end_value)) = (start.parse::<u64>(), end.parse::<u64>()) else {
+        return false;
+    };
+    if end.len() < start.len() {
+        // abbreviated ranges, like `2024-25`, repeat the start's leading digits
+        end.len() <= 2
+            && start[start.len() - end.len()..]
+                .parse::<u64>()
+                .is_ok_and(|value| value < end_value)
+    } else {
+        start_value < end_value && (end.len() == start.len() || start.len() <= 2)
+    }
+}
+
+/// Replaces the hyphen in number ranges, like `10-20`, with an en dash.  Longer sequences, such as
+/// `2024-01-31` dates, and pairs which do not read as ascending ranges, such as `555-1234`, are
+/// left unchanged.
+fn en_dash_ranges(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut result = String::with_capacity(line.len());
+    for (index, c) in chars.iter().enumerate() {
+        if *c == '-' && index > 0 && index + 1 < chars.len() {
+            let before = chars[..index]
+                .iter()
+                .rev()
+                .position(|value| !value.is_ascii_digit())
+                .map_or(0, |position| index - position);
+            let after = chars[index + 1..]
+                .iter()
+                .position(|value| !value.is_ascii_digit())
+                .map_or(chars.len(), |position| index + 1 + position);
+            let bounded = |value: Option<&char>| {
+                value.is_none_or(|value| !(value.is_alphanumeric() || *value == '-'))
+            };
+            if before < index
+                && after > index + 1
+                && bounded(
+                    before
+                        .checked_sub(1)
+                        .and_then(|position| chars.get(position)),
+                )
+                && bounded(chars.get(after))
+                && plausible_range(
+                    &chars[before..index].iter().collect::<String>(),
+                    &chars[index + 1..after].iter().collect::<String>(),
+                )
+            {
+                result.push('\u{2013}');
+                continue;
             }
         }
-        Cow::Owned(result)
+        result.push(*c);
+    }
+    result
+}
+
+/// Puts narrow no-break spaces before `;`, `:`, `!`, `?` and `»`, and after `«`, replacing any
+/// typed space.  Colons in times, like `12:30`, and in URLs are left alone.
+fn french_spacing(line: &str) -> String {
+    const BEFORE: &str = ";:!?\u{bb}";
+
+    let chars: Vec<char> = line.chars().collect();
+    let mut result = String::with_capacity(line.len());
+    for (index, c) in chars.iter().enumerate() {
+        let next = chars.get(index + 1);
+        let previous = index
+            .checked_sub(1)
+            .and_then(|position| chars.get(position));
+        if *c == ' '
+            && (next.is_some_and(|value| BEFORE.contains(*value))
+                || previous.is_some_and(|value| *value == '\u{ab}'))
+        {
+            result.push(NARROW_NO_BREAK_SPACE);
+            continue;
+        }
+        let spaced = |value: Option<&char>| value.is_none_or(|value| value.is_whitespace());
+        let follows_punctuation =
+            *c != '\u{bb}' && previous.is_some_and(|value| ";:!?".contains(*value));
+        let url_or_time = *c == ':'
+            && (next == Some(&'/')
+                || (previous.is_some_and(char::is_ascii_digit)
+                    && next.is_some_and(char::is_ascii_digit)));
+        if BEFORE.contains(*c)
+            && !spaced(previous)
+            && previous != Some(&'\u{ab}')
+            && !follows_punctuation
+            && !url_or_time
+        {
+            result.push(NARROW_NO_BREAK_SPACE);
+        }
+        result.push(*c);
+        if *c == '\u{ab}' && !spaced(next) && next != Some(&'\u{bb}') {
+            result.push(NARROW_NO_BREAK_SPACE);
+        }
+    }
+    result
+}
+
+/// Applies `locale` quotation marks, dashes, ellipses, number range en dashes and punctuation
+/// spacing to `line`.  `previous` is the character before `line`, if it continues earlier text.
+pub fn typeset(line: &str, locale: Locale, previous: Option<char>) -> String {
+    let result = convert_quotes(line, &locale.quote_style(), previous);
+    let result = en_dash_ranges(&dashes_and_ellipses(&result));
+    if locale == Locale::French {
+        french_spacing(&result)
     } else {
-        line
+        result
     }
 }
@@ -1,43 +1,127 @@
-use crate::text::format_line;
+use crate::text::{Locale, expand_shortcodes, find_emoji, remove_emoji, typeset};
 
 #[test]
 fn format_line_replaces_inner_apostrophe() {
     let line = "My apple's quite tasty.";
-    let result = format_line(line);
+    let result = typeset(line, Locale::English, None);
     assert_eq!(result, "My apple’s quite tasty.");
 }
 
 #[test]
 fn test_line_replaces_outer_apostrophe() {
     let line = "My trees' apples are tasty.";
-    let result = format_line(line);
+    let result = typeset(line, Locale::English, None);
     assert_eq!(result, "My trees’ apples are tasty.");
 }
 
 #[test]
 fn test_line_adds_double_smart_quotes() {
     let line = r#"The person said "My apple is quite tasty.""#;
-    let result = format_line(line);
+    let result = typeset(line, Locale::English, None);
     assert_eq!(result, r#"The person said “My apple is quite tasty.”"#);
 }
 
 #[test]
 fn test_line_adds_single_smart_quotes() {
     let line = "My apple is quite 'tasty'.";
-    let result = format_line(line);
+    let result = typeset(line, Locale::English, None);
     assert_eq!(result, "My apple is quite ‘tasty’.");
 }
 
 #[test]
 fn test_line_replaces_unmatched_single_double_quote_pairs() {
     let line = r#"My apple is quite 'tasty"."#;
-    let result = format_line(line);
+    let result = typeset(line, Locale::English, None);
     assert_eq!(result, "My apple is quite ‘tasty”.");
 }
 
 #[test]
 fn test_line_does_nothing_when_line_has_no_quotes_or_apostrophes() {
     let line = "My apple is quite tasty.";
-    let result = format_line(line);
+    let result = typeset(line, Locale::English, None);
     assert_eq!(result, "My apple is quite tasty.");
 }
+
+#[test]
+fn locale_from_language_tag_selects_regional_styles() {
+    assert_eq!(Locale::from_language_tag("de"), Locale::German);
+    assert_eq!(Locale::from_language_tag("de-CH"), Locale::Swiss);
+    assert_eq!(Locale::from_language_tag("fr_FR"), Locale::French);
+    assert_eq!(Locale::from_language_tag("sv-SE"), Locale::Swedish);
+    assert_eq!(Locale::from_language_tag("en-GB"), Locale::English);
+}
+
+#[test]
+fn typeset_uses_german_quotes() {
+    let line = r#"Er sagte "Das ist 'gut'" und ging's an."#;
+    let result = typeset(line, Locale::German, None);
+    assert_eq!(result, "Er sagte „Das ist ‚gut‘“ und ging’s an.");
+}
+
+#[test]
+fn typeset_uses_french_quotes_and_spacing() {
+    let line = r#"Il a dit "Bonjour" : vraiment ?"#;
+    let result = typeset(line, Locale::French, None);
+    assert_eq!(
+        result,
+        "Il a dit «\u{202f}Bonjour\u{202f}»\u{202f}: vraiment\u{202f}?"
+    );
+}
+
+#[test]
+fn typeset_adds_french_spacing_where_none_was_typed() {
+    let line = "Bonjour! «Ça va?» Oui; à 12:30, voir https://example.com";
+    let result = typeset(line, Locale::French, None);
+    assert_eq!(
+        result,
+        "Bonjour\u{202f}! «\u{202f}Ça va\u{202f}?\u{202f}» Oui\u{202f}; à 12:30, voir https://example.com"
+    );
+}
+
+#[test]
+fn typeset_closes_quote_continuing_earlier_text() {
+    let line = "\" she said";
+    let result = typeset(line, Locale::Swiss, Some('d'));
+    assert_eq!(result, "» she said");
+}
+
+#[test]
+fn typeset_adds_en_dash_to_number_ranges_only() {
+    let line = "Pages 10-20, on 2024-01-31... well -- mostly";
+    let result = typeset(line, Locale::English, None);
+    assert_eq!(result, "Pages 10–20, on 2024-01-31… well – mostly");
+
+    let line = "From 9-12, in 2024-25 or 1-100";
+    let result = typeset(line, Locale::English, None);
+    assert_eq!(result, "From 9–12, in 2024–25 or 1–100");
+}
+
+#[test]
+fn typeset_keeps_hyphens_in_phone_and_part_numbers() {
+    let line = "Call 555-1234 or 0800-123, quoting part 20-10 and ZIP 12345-6789";
+    let result = typeset(line, Locale::English, None);
+    assert_eq!(result, line);
+}
+
+#[test]
+fn expand_shortcodes_replaces_known_shortcodes() {
+    assert_eq!(
+        expand_shortcodes(":tada: at 10:30:00, see:rocket: or :not_an_emoji:"),
+        "🎉 at 10:30:00, see:rocket: or :not_an_emoji:"
+    );
+    assert_eq!(expand_shortcodes(":+1::heart:"), "👍❤️");
+}
+
+#[test]
+fn find_emoji_matches_sequences_but_not_text_symbols() {
+    let text = "Hi 👋🏽, © 2024 👨‍👩‍👧";
+    let names: Vec<&str> = find_emoji(text)
+        .iter()
+        .map(|emoji_match| emoji_match.emoji.name())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["waving hand: medium skin tone", "family: man, woman, girl"]
+    );
+    assert_eq!(remove_emoji(text), "Hi , © 2024 ");
+}
@@ -1,4 +1,4 @@
-use url::Url;
+use url::{Url, form_urlencoded};
 
 pub fn relative_url(url: &str) -> bool {
     match Url::parse(url) {
@@ -6,3 +6,114 @@ pub fn relative_url(url: &str) -> bool {
         Ok(_) | Err(_) => false,
     }
 }
+
+/// Returns `true` if the host of `url` is `domain` or one of its subdomains
+pub fn url_in_domain(url: &str, domain: &str) -> bool {
+    let Ok(parsed_url) = Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = parsed_url.host_str() else {
+        return false;
+    };
+    let domain = domain.trim_start_matches("www.");
+    let host = host.trim_start_matches("www.");
+    host.eq_ignore_ascii_case(domain)
+        || host
+            .to_ascii_lowercase()
+            .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+}
+
+/// Host of `url`, if it is an absolute URL with a host
+pub fn url_host(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|value| value.host_str().map(str::to_string))
+}
+
+/// Query parameters to append to links whose host is `domain` or one of its subdomains
+#[derive(Debug)]
+pub struct QueryParameterRule<'a> {
+    domain: &'a str,
+    parameters: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> QueryParameterRule<'a> {
+    pub fn new(domain: &'a str, parameters: Vec<(&'a str, &'a str)>) -> Self {
+        QueryParameterRule { domain, parameters }
+    }
+}
+
+/// Rewrites absolute link URLs, removing tracking query parameters and appending campaign ones
+#[derive(Debug, Default)]
+pub struct UrlRewriter<'a> {
+    append_parameters: Vec<QueryParameterRule<'a>>,
+    strip_parameters: Vec<&'a str>,
+}
+
+impl<'a> UrlRewriter<'a> {
+    pub fn append_parameters(&mut self, value: Vec<QueryParameterRule<'a>>) -> &mut Self {
+        self.append_parameters = value;
+        self
+    }
+
+    /// Query parameter names to remove, a trailing `*` matches any name with that prefix (`utm_*`)
+    pub fn strip_parameters(&mut self, value: Vec<&'a str>) -> &mut Self {
+        self.strip_parameters = value;
+        self
+    }
+
+    fn is_stripped(&self, name: &str) -> bool {
+        self.strip_parameters
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == *pattern,
+            })
+    }
+
+    /// Returns the rewritten URL, or `None` when `url` is relative, unparsable or left unchanged.
+    /// Kept query parameters are copied as written, without re-encoding.
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        if self.append_parameters.is_empty() && self.strip_parameters.is_empty() {
+            return None;
+        }
+        let mut parsed_url = Url::parse(url).ok()?;
+        let appended: Vec<(&str, &str)> = self
+            .append_parameters
+            .iter()
+            .filter(|rule| url_in_domain(url, rule.domain))
+            .flat_map(|rule| rule.parameters.iter().copied())
+            .collect();
+        let existing: Vec<&str> = parsed_url.query().map_or_else(Vec::new, |query| {
+            query.split('&').filter(|value| !value.is_empty()).collect()
+        });
+        let kept: Vec<&str> = existing
+            .iter()
+            .copied()
+            .filter(|segment| {
+                let name = form_urlencoded::parse(segment.as_bytes())
+                    .next()
+                    .map(|(name, _)| name)
+                    .unwrap_or_default();
+                !self.is_stripped(&name) && !appended.iter().any(|(key, _)| *key == name)
+            })
+            .collect();
+        if appended.is_empty() && kept.len() == existing.len() {
+            return None;
+        }
+
+        let mut query = kept.join("&");
+        if !appended.is_empty() {
+            if !query.is_empty() {
+                query.push('&');
+            }
+            query.push_str(
+                &form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(appended)
+                    .finish(),
+            );
+        }
+        parsed_url.set_query((!query.is_empty()).then_some(query.as_str()));
+        Some(parsed_url.to_string())
+    }
+}
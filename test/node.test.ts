@@ -1,5 +1,9 @@
 import {
+  html_to_markdown as htmlToMarkdown,
+  html_to_plaintext as htmlToPlaintext,
   markdown_to_html as markdownToHtml,
+  markdown_to_markdown as markdownToMarkdown,
+  markdown_to_mdast as markdownToMdast,
   markdown_to_plaintext as markdownToPlaintext,
   mjml_to_html as mjmlToHtml,
 } from "@rodneylab/parsedown";
@@ -32,6 +36,7 @@ test("it parses markdown to html", async () => {
   expect(headings?.[0]).toStrictEqual({
     heading: "👋🏽 Hello You",
     id: "wave-hello-you",
+    level: 2,
   });
   expect(html).toBe(
     `<h2 id="wave-hello-you">👋🏽 Hello You <a href="#wave-hello-you" class="heading-anchor">#</a></h2>
@@ -81,7 +86,6 @@ test("it parses markdown to plain text", () => {
     `👋🏽 Hello You
 
 - alpha
-
 - beta
 
 Example Link (https://example.com/)
@@ -89,6 +93,138 @@ Example Link (https://example.com/)
   );
 });
 
+test("it wraps plaintext lines and lists link references", () => {
+  // arrange
+  const markdown =
+    "A sentence with [a link](https://example.com/) that goes on for a while.";
+
+  // act
+  const plaintext = markdownToPlaintext(markdown, {
+    line_length: 30,
+    link_style: "reference",
+  });
+
+  // assert
+  expect(plaintext).toBe(
+    `A sentence with a link [1]
+that goes on for a while.
+
+[1] https://example.com/
+`,
+  );
+});
+
+test("it converts html to plain text", () => {
+  // arrange
+  const html =
+    '<h2>Hello You</h2><p>Read the <a href="/docs">docs</a> <tool-tip>hidden</tool-tip>now.</p><ul><li>alpha</li><li>beta</li></ul>';
+
+  // act
+  const plaintext = htmlToPlaintext(html, {
+    canonical_root_url: "https://example.com",
+    heading_style: "uppercase",
+  });
+
+  // assert
+  assert(typeof htmlToPlaintext === "function");
+  expect(plaintext).toBe(
+    `HELLO YOU
+
+Read the docs (https://example.com/docs) now.
+
+- alpha
+- beta
+`,
+  );
+});
+
+test("it converts html to markdown", () => {
+  // arrange
+  const html =
+    '<h2>Hello You</h2><p>Some <strong>bold</strong> and <a href="https://example.com/">a link</a>.</p><ul><li>alpha</li><li>beta</li></ul>';
+
+  // act
+  const markdown = htmlToMarkdown(html);
+
+  // assert
+  assert(typeof htmlToMarkdown === "function");
+  expect(markdown).toBe(
+    `## Hello You
+
+Some **bold** and [a link](https://example.com/).
+
+- alpha
+- beta
+`,
+  );
+});
+
+test("it formats markdown", () => {
+  // arrange
+  const markdown = `Hello You
+=========
+
+* alpha
+* beta
+
+A sentence that goes on for a while and wraps.
+`;
+
+  // act
+  const formatted = markdownToMarkdown(markdown, { line_length: 20 });
+
+  // assert
+  assert(typeof markdownToMarkdown === "function");
+  expect(formatted).toBe(
+    `# Hello You
+
+- alpha
+- beta
+
+A sentence that goes
+on for a while and
+wraps.
+`,
+  );
+});
+
+test("it parses markdown to an mdast tree", () => {
+  // arrange
+  const markdown = "## Hello\n\nSome *text*\n";
+
+  // act
+  const tree = markdownToMdast(markdown, {});
+
+  // assert
+  assert(typeof markdownToMdast === "function");
+  expect(tree.type).toBe("root");
+  expect(tree.position).toStrictEqual({
+    start: { line: 1, column: 1, offset: 0 },
+    end: { line: 4, column: 1, offset: 22 },
+  });
+  const [heading, paragraph] = tree.children;
+  expect(heading).toStrictEqual({
+    type: "heading",
+    depth: 2,
+    children: [
+      {
+        type: "text",
+        value: "Hello",
+        position: {
+          start: { line: 1, column: 4, offset: 3 },
+          end: { line: 1, column: 9, offset: 8 },
+        },
+      },
+    ],
+    data: { id: "hello", hProperties: { id: "hello" } },
+    position: {
+      start: { line: 1, column: 1, offset: 0 },
+      end: { line: 1, column: 9, offset: 8 },
+    },
+  });
+  expect(paragraph.type).toBe("paragraph");
+});
+
 test("it parses mjml to html", () => {
   // arrange
   const mjml = '<mjml lang="en-GB"></mjml>';